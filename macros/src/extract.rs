@@ -0,0 +1,1623 @@
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::{abort, emit_error};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    braced, bracketed,
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token,
+    visit_mut::{self, VisitMut},
+    Attribute, Block, ConstParam, Expr, ExprAwait, FnArg, ForeignItemFn, GenericArgument,
+    GenericParam, Generics, Ident, ImplItem, Item, ItemImpl, ItemMod, Lifetime, LitStr, Meta, Pat,
+    PatIdent, PatType, Path, PathArguments, ReturnType, Signature, Token, Type, TypeParam,
+    TypePath, TypeReference, Visibility, WherePredicate,
+};
+
+use crate::generate;
+
+/// The cases accepted by `rename_all`, which are the same as those of `serde`.
+const CASES: [(&str, Case); 8] = [
+    ("lowercase", Case::Flat),
+    ("UPPERCASE", Case::UpperFlat),
+    ("PascalCase", Case::Pascal),
+    ("camelCase", Case::Camel),
+    ("snake_case", Case::Snake),
+    ("SCREAMING_SNAKE_CASE", Case::UpperSnake),
+    ("kebab-case", Case::Kebab),
+    ("SCREAMING-KEBAB-CASE", Case::UpperKebab),
+];
+
+/// Parses the value of an argument written as `name = value`, after `name` has already been parsed.
+fn value<T: Parse>(input: ParseStream) -> syn::Result<T> {
+    input.parse::<Token![=]>()?;
+    input.parse()
+}
+
+/// Parses the value of an argument that can be written as either `name` or `name = value`, after `name` has already
+/// been parsed.
+fn optional_value<T: Parse>(input: ParseStream) -> syn::Result<Option<T>> {
+    if input.peek(Token![=]) {
+        value(input).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns the name of an argument if the feature that it requires is enabled, or an error asking for it otherwise.
+fn require_feature(ident: Ident, enabled: bool, feature: &str) -> syn::Result<Ident> {
+    if enabled {
+        Ok(ident)
+    } else {
+        Err(syn::Error::new(
+            ident.span(),
+            format!(
+                "`{}` requires the `{}` feature to be enabled",
+                ident, feature
+            ),
+        ))
+    }
+}
+
+/// The span of an argument of the macro attribute, which is that of its name if it is stored along with its value.
+trait ArgSpan {
+    fn arg_span(&self) -> Span;
+}
+impl ArgSpan for Ident {
+    fn arg_span(&self) -> Span {
+        self.span()
+    }
+}
+impl ArgSpan for Type {
+    fn arg_span(&self) -> Span {
+        self.span()
+    }
+}
+impl<T> ArgSpan for (Ident, T) {
+    fn arg_span(&self) -> Span {
+        self.0.span()
+    }
+}
+impl<T, U> ArgSpan for (Ident, T, U) {
+    fn arg_span(&self) -> Span {
+        self.0.span()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Args {
+    pub vis: Option<Visibility>,
+    pub enum_vis: Option<Visibility>,
+    pub map_vis: Option<Visibility>,
+    pub by_ref: Option<Ident>,
+    pub by_mut: Option<Ident>,
+    pub map_ref: Option<Ident>,
+    pub lifetime: Option<(Ident, Option<Lifetime>)>,
+    pub tuple_variants: Option<Ident>,
+    pub unit_variants: Option<Ident>,
+    pub arg_structs: Option<Ident>,
+    pub no_map: Option<Ident>,
+    pub map_name: Option<Ident>,
+    pub non_exhaustive: Option<Ident>,
+    pub wildcard: Option<Expr>,
+    pub prefix: Option<LitStr>,
+    pub suffix: Option<LitStr>,
+    /// The case that the names of the functions are converted into wherever they are used as strings.
+    pub rename_all: Option<Case>,
+    pub module: Option<Ident>,
+    pub boxed_future: Option<Ident>,
+    pub force_async: Option<Ident>,
+    pub assume_safe: Option<Ident>,
+    pub maybe_async: Option<Ident>,
+    /// Whether the fields of the variants are recorded by the spans of `tracing`.
+    pub tracing: Option<(Ident, bool)>,
+    pub log: Option<Ident>,
+    /// The name of the histogram that `metrics` records the duration of each call in, if it is given.
+    pub metrics: Option<(Ident, Option<LitStr>)>,
+    pub box_return: Option<(Ident, Option<Path>)>,
+    /// The name of the `enum` holding the values returned by the functions, if it is given, and the traits that it
+    /// derives.
+    pub output_enum: Option<(Ident, Option<Ident>, Vec<Path>)>,
+    pub return_as: Option<Type>,
+    pub try_map: Option<Type>,
+    pub map_catch: Option<Ident>,
+    pub timed: Option<Ident>,
+    pub context: Option<Type>,
+    pub inline: Option<Meta>,
+    pub free_fn: Option<(Ident, Option<Ident>)>,
+    pub ffi: Option<(Ident, Option<Ident>)>,
+    pub match_macro: Option<(Ident, Option<Ident>)>,
+    pub private_functions: Option<Ident>,
+    pub display: Option<Ident>,
+    pub debug: Option<Ident>,
+    pub from_str: Option<Ident>,
+    pub dispatch_by_name: Option<Ident>,
+    pub registry: Option<Ident>,
+    pub variants: Option<Ident>,
+    pub strum_like: Option<Ident>,
+    pub count: Option<Ident>,
+    pub ordering: Option<Ident>,
+    pub name: Option<Ident>,
+    pub names: Option<Ident>,
+    pub kind: Option<(Ident, Option<Ident>)>,
+    pub accessors: Option<Ident>,
+    pub visitor: Option<(Ident, Option<Ident>)>,
+    pub repr_conversions: Option<Ident>,
+    pub as_fn: Option<Ident>,
+    pub jump_table: Option<Ident>,
+    pub memoize: Option<Ident>,
+    pub generate_tests: Option<Ident>,
+    pub map_all: Option<Ident>,
+    pub map_batch: Option<Ident>,
+    pub map_batch_par: Option<Ident>,
+    pub map_batch_concurrent: Option<Ident>,
+    pub constructors: Option<Ident>,
+    pub dispatch: Option<Ident>,
+    pub dyn_dispatch: Option<Ident>,
+    pub into_fn: Option<Ident>,
+    pub serde: Option<Ident>,
+    pub schemars: Option<Ident>,
+    pub enum_map: Option<Ident>,
+    pub arbitrary: Option<Ident>,
+    pub wasm: Option<Ident>,
+    pub dispatch_json: Option<Ident>,
+    pub pyo3: Option<Ident>,
+    pub napi: Option<Ident>,
+    /// The `clap` trait to derive for the `enum`, if any.
+    pub clap: Option<(Ident, Ident)>,
+    /// The name of the macro defined by an `impl` block marked with `extend`, which adds its functions to the `enum`.
+    pub extend: Option<(Ident, Ident)>,
+    /// The macros defined by the `impl` blocks marked with `extend` whose functions are added to the `enum`.
+    pub extended_by: Option<(Ident, Vec<Path>)>,
+    pub external: Option<(Ident, Vec<Path>)>,
+    /// The `enum`s that `map` delegates to through a variant holding each of them.
+    pub nest: Option<(Ident, Vec<Path>)>,
+    pub custom: Option<(Ident, Option<Ident>)>,
+}
+impl Args {
+    /// The argument that causes `map` to borrow the `enum` (if any).
+    pub fn borrow(&self) -> Option<&Ident> {
+        self.by_ref.as_ref().or(self.by_mut.as_ref())
+    }
+
+    /// The name of the variant holding a closure given to `custom`, if there is one.
+    pub fn custom_ident(&self) -> Option<Ident> {
+        self.custom.as_ref().map(|(custom, name)| {
+            name.clone()
+                .unwrap_or_else(|| Ident::new("Custom", custom.span()))
+        })
+    }
+
+    /// Whether the `enum` has variants besides those of the functions (and the hidden one holding unused generic
+    /// parameters).
+    pub fn extra_variants(&self) -> bool {
+        self.nest.is_some() || self.custom.is_some()
+    }
+
+    /// The lifetime of the shared references stored in the variants, when they aren't stored by their owned
+    /// counterpart.
+    pub fn lifetime(&self) -> Option<Lifetime> {
+        self.lifetime
+            .as_ref()
+            .map(|(_, lifetime)| lifetime.clone().unwrap_or_else(|| parse_quote!('a)))
+    }
+
+    /// The type that the return values of the functions are boxed in, if they are.
+    pub fn box_return(&self) -> Option<Path> {
+        if let Some(Type::Path(TypePath { path, .. })) = &self.return_as {
+            let mut path = path.clone();
+            if let Some(segment) = path.segments.last_mut() {
+                segment.arguments = PathArguments::None;
+            }
+            return Some(path);
+        }
+
+        self.box_return.as_ref().map(|(_, path)| {
+            path.clone()
+                .unwrap_or_else(|| parse_quote!(::enum_from_functions::__private::Box))
+        })
+    }
+
+    /// The name of the generated `map` function.
+    pub fn map_ident(&self) -> Ident {
+        self.map_name
+            .clone()
+            .unwrap_or_else(|| format_ident!("map"))
+    }
+
+    /// The visibility of the generated `enum`.
+    pub fn enum_vis(&self) -> Option<&Visibility> {
+        self.enum_vis.as_ref().or(self.vis.as_ref())
+    }
+
+    /// The visibility of the generated `map` function.
+    pub fn map_vis(&self) -> Option<&Visibility> {
+        self.map_vis.as_ref().or(self.vis.as_ref())
+    }
+
+    /// The visibility of the generated `enum` and of the items generated alongside it, adjusted for when they are placed
+    /// in a separate module.
+    pub fn nested_enum_vis(&self) -> Option<Visibility> {
+        match self.module {
+            Some(_) => Some(generate::nest_visibility(self.enum_vis())),
+            None => self.enum_vis().cloned(),
+        }
+    }
+
+    /// The visibility of the generated `map` function and of the functions like it, adjusted for when they are placed
+    /// in a separate module.
+    pub fn nested_map_vis(&self) -> Option<Visibility> {
+        match self.module {
+            Some(_) => Some(generate::nest_visibility(self.map_vis())),
+            None => self.map_vis().cloned(),
+        }
+    }
+}
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut r = Args::default();
+        let mut count = 0;
+
+        while !input.is_empty() {
+            count += 1;
+            if input.peek(Token![pub]) {
+                r.vis = Some(input.parse()?);
+            } else {
+                let ident = input.call(Ident::parse_any)?;
+                match ident.to_string().as_str() {
+                    "by_ref" => r.by_ref = Some(ident),
+                    "by_mut" => r.by_mut = Some(ident),
+                    "map_ref" => r.map_ref = Some(ident),
+                    "map_catch" => r.map_catch = Some(ident),
+                    "timed" => r.timed = Some(ident),
+                    "extend" => r.extend = Some((ident, value(input)?)),
+                    "tuple_variants" => r.tuple_variants = Some(ident),
+                    "unit_variants" => r.unit_variants = Some(ident),
+                    "arg_structs" => r.arg_structs = Some(ident),
+                    "no_map" => r.no_map = Some(ident),
+                    "boxed_future" => r.boxed_future = Some(ident),
+                    "force_async" => r.force_async = Some(ident),
+                    "assume_safe" => r.assume_safe = Some(ident),
+                    "maybe_async" => r.maybe_async = Some(ident),
+                    "private_functions" => r.private_functions = Some(ident),
+                    "display" => r.display = Some(ident),
+                    "debug" => r.debug = Some(ident),
+                    "from_str" => r.from_str = Some(ident),
+                    "dispatch_by_name" => r.dispatch_by_name = Some(ident),
+                    "variants" => r.variants = Some(ident),
+                    "strum_like" => r.strum_like = Some(ident),
+                    "count" => r.count = Some(ident),
+                    "ordering" => r.ordering = Some(ident),
+                    "name" => r.name = Some(ident),
+                    "names" => r.names = Some(ident),
+                    "accessors" => r.accessors = Some(ident),
+                    "visitor" => r.visitor = Some((ident, optional_value(input)?)),
+                    "repr_conversions" => r.repr_conversions = Some(ident),
+                    "as_fn" => r.as_fn = Some(ident),
+                    "jump_table" => r.jump_table = Some(ident),
+                    "memoize" => r.memoize = Some(ident),
+                    "generate_tests" => r.generate_tests = Some(ident),
+                    "map_all" => r.map_all = Some(ident),
+                    "map_batch" => r.map_batch = Some(ident),
+                    "constructors" => r.constructors = Some(ident),
+                    "dispatch" => r.dispatch = Some(ident),
+                    "dyn_dispatch" => r.dyn_dispatch = Some(ident),
+                    "into_fn" => r.into_fn = Some(ident),
+                    // The name of the fieldless `enum` may optionally be provided.
+                    "kind" => r.kind = Some((ident, optional_value(input)?)),
+                    // The name of the function may optionally be provided.
+                    "free_fn" => r.free_fn = Some((ident, optional_value(input)?)),
+                    "ffi" => r.ffi = Some((ident, optional_value(input)?)),
+                    // The name of the macro may optionally be provided.
+                    "match_macro" => r.match_macro = Some((ident, optional_value(input)?)),
+                    // The name of the `enum` may optionally be provided, followed by the traits that it derives
+                    // (mirroring the syntax of the `#[derive(...)]` attribute).
+                    "output_enum" => {
+                        let name = optional_value(input)?;
+                        let mut derives = Vec::new();
+                        if input.peek(token::Paren) {
+                            let content;
+                            parenthesized!(content in input);
+                            let derive = content.call(Ident::parse_any)?;
+                            if derive != "derive" {
+                                return Err(syn::Error::new(derive.span(), "expected `derive`"));
+                            }
+                            let paths;
+                            parenthesized!(paths in content);
+                            derives = Punctuated::<Path, Token![,]>::parse_terminated(&paths)?
+                                .into_iter()
+                                .collect();
+                        }
+                        r.output_enum = Some((ident, name, derives));
+                    }
+                    // The type of the box may optionally be provided.
+                    "box_return" => r.box_return = Some((ident, optional_value(input)?)),
+                    // The name of the lifetime may optionally be provided.
+                    "lifetime" => r.lifetime = Some((ident, optional_value(input)?)),
+                    "inline" => {
+                        // Mirrors the syntax of the `#[inline]` attribute itself.
+                        r.inline = Some(if input.peek(token::Paren) {
+                            let content;
+                            parenthesized!(content in input);
+                            let hint = content.call(Ident::parse_any)?;
+                            if hint != "always" && hint != "never" {
+                                return Err(syn::Error::new(
+                                    hint.span(),
+                                    "expected `always` or `never`",
+                                ));
+                            }
+                            parse_quote!(#ident(#hint))
+                        } else {
+                            parse_quote!(#ident)
+                        });
+                    }
+                    "enum_vis" => r.enum_vis = Some(value(input)?),
+                    "map_vis" => r.map_vis = Some(value(input)?),
+                    "prefix" => {
+                        let prefix: LitStr = value(input)?;
+                        if syn::parse_str::<Ident>(&format!("{}Variant", prefix.value())).is_err() {
+                            return Err(syn::Error::new(
+                                prefix.span(),
+                                "prefix cannot be used to start an identifier",
+                            ));
+                        }
+                        r.prefix = Some(prefix);
+                    }
+                    "suffix" => {
+                        let suffix: LitStr = value(input)?;
+                        if syn::parse_str::<Ident>(&format!("Variant{}", suffix.value())).is_err() {
+                            return Err(syn::Error::new(
+                                suffix.span(),
+                                "suffix cannot be used to end an identifier",
+                            ));
+                        }
+                        r.suffix = Some(suffix);
+                    }
+                    "rename_all" => {
+                        let case: LitStr = value(input)?;
+                        r.rename_all = Some(
+                            CASES
+                                .iter()
+                                .find(|(name, _)| *name == case.value())
+                                .map(|(_, case)| *case)
+                                .ok_or_else(|| {
+                                    let (last, rest) = CASES.split_last().unwrap();
+                                    let rest = rest.iter().map(|(name, _)| format!("{:?}", name));
+                                    syn::Error::new(
+                                        case.span(),
+                                        format!(
+                                            "expected one of {} or {:?}",
+                                            rest.collect::<Vec<_>>().join(", "),
+                                            last.0
+                                        ),
+                                    )
+                                })?,
+                        );
+                    }
+                    "module" => r.module = Some(value(input)?),
+                    "map_name" => r.map_name = Some(value(input)?),
+                    "extended_by" => {
+                        let content;
+                        parenthesized!(content in input);
+                        let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                        r.extended_by = Some((ident, paths.into_iter().collect()));
+                    }
+                    "external" => {
+                        let content;
+                        parenthesized!(content in input);
+                        let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                        r.external = Some((ident, paths.into_iter().collect()));
+                    }
+                    "custom" => r.custom = Some((ident, optional_value(input)?)),
+                    "nest" => {
+                        let content;
+                        parenthesized!(content in input);
+                        let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                        r.nest = Some((ident, paths.into_iter().collect()));
+                    }
+                    "try_map" => r.try_map = Some(value(input)?),
+                    "context" => r.context = Some(value(input)?),
+                    "return_as" => {
+                        // The values are put into the type by calling `new` on it, as with `Box::new`.
+                        let return_as: Type = value(input)?;
+                        if !matches!(
+                            &return_as,
+                            Type::Path(TypePath { qself: None, path })
+                                if path.segments.last().is_some_and(|segment| !segment.arguments.is_none())
+                        ) {
+                            return Err(syn::Error::new(
+                                return_as.span(),
+                                "expected a type such as `Box<dyn Trait>`",
+                            ));
+                        }
+                        r.return_as = Some(return_as);
+                    }
+                    // The code generated for these arguments refers to other crates, so they must be opted into.
+                    "serde" => {
+                        r.serde = Some(require_feature(ident, cfg!(feature = "serde"), "serde")?)
+                    }
+                    "arbitrary" => {
+                        r.arbitrary = Some(require_feature(ident, cfg!(feature = "fuzz"), "fuzz")?)
+                    }
+                    "wasm" => {
+                        r.wasm = Some(require_feature(ident, cfg!(feature = "wasm"), "wasm")?)
+                    }
+                    "schemars" => {
+                        r.schemars = Some(require_feature(
+                            ident,
+                            cfg!(feature = "schemars"),
+                            "schemars",
+                        )?)
+                    }
+                    "enum_map" => {
+                        r.enum_map = Some(require_feature(
+                            ident,
+                            cfg!(feature = "enum_map"),
+                            "enum_map",
+                        )?)
+                    }
+                    "dispatch_json" => {
+                        r.dispatch_json = Some(require_feature(
+                            ident,
+                            cfg!(feature = "serde_json"),
+                            "serde_json",
+                        )?)
+                    }
+                    "pyo3" => {
+                        r.pyo3 = Some(require_feature(ident, cfg!(feature = "pyo3"), "pyo3")?)
+                    }
+                    "napi" => {
+                        r.napi = Some(require_feature(ident, cfg!(feature = "napi"), "napi")?)
+                    }
+                    "tracing" => {
+                        let ident = require_feature(ident, cfg!(feature = "tracing"), "tracing")?;
+                        let fields = match optional_value::<Ident>(input)? {
+                            Some(fields) if fields == "fields" => true,
+                            Some(other) => {
+                                return Err(syn::Error::new(other.span(), "expected `fields`"))
+                            }
+                            None => false,
+                        };
+                        r.tracing = Some((ident, fields));
+                    }
+                    "log" => r.log = Some(require_feature(ident, cfg!(feature = "log"), "log")?),
+                    "metrics" => {
+                        let ident = require_feature(ident, cfg!(feature = "metrics"), "metrics")?;
+                        r.metrics = Some((ident, optional_value(input)?))
+                    }
+                    "registry" => {
+                        r.registry = Some(require_feature(
+                            ident,
+                            cfg!(feature = "inventory"),
+                            "inventory",
+                        )?)
+                    }
+                    "map_batch_par" => {
+                        r.map_batch_par =
+                            Some(require_feature(ident, cfg!(feature = "rayon"), "rayon")?)
+                    }
+                    "map_batch_concurrent" => {
+                        r.map_batch_concurrent =
+                            Some(require_feature(ident, cfg!(feature = "tokio"), "tokio")?)
+                    }
+                    "clap" => {
+                        let ident = require_feature(ident, cfg!(feature = "clap"), "clap")?;
+                        // `Parser` is derived unless another trait is given.
+                        let derive = optional_value::<Ident>(input)?
+                            .unwrap_or_else(|| Ident::new("Parser", ident.span()));
+                        if !["Parser", "Subcommand", "ValueEnum"].contains(&&*derive.to_string()) {
+                            return Err(syn::Error::new(
+                                derive.span(),
+                                "expected `Parser`, `Subcommand` or `ValueEnum`",
+                            ));
+                        }
+                        r.clap = Some((ident, derive));
+                    }
+                    "non_exhaustive" => {
+                        r.non_exhaustive = Some(ident);
+
+                        // An expression may optionally be provided to be used for the wildcard arm in `map`.
+                        r.wildcard = optional_value(input)?;
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            format!("unknown argument `{}`", ident),
+                        ))
+                    }
+                }
+            }
+
+            // Arguments are separated by commas, with an optional trailing comma.
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        // The other arguments are provided to the `impl` block that generates the `enum`.
+        if let Some((extend, _)) = r.extend.as_ref().filter(|_| count > 1) {
+            return Err(syn::Error::new(
+                extend.span(),
+                "`extend` cannot be combined with other arguments",
+            ));
+        }
+
+        macro_rules! exclusive {
+            ( $first:ident, $second:ident ) => {
+                if let (Some(first), Some(second)) = (&r.$first, &r.$second) {
+                    let message = concat!(
+                        "cannot combine `",
+                        stringify!($first),
+                        "` and `",
+                        stringify!($second),
+                        "`"
+                    );
+                    let mut err = syn::Error::new(first.arg_span(), message);
+                    err.combine(syn::Error::new(second.arg_span(), message));
+                    return Err(err);
+                }
+            };
+        }
+        exclusive!(by_ref, by_mut);
+        exclusive!(by_ref, map_ref);
+        exclusive!(by_mut, map_ref);
+        exclusive!(tuple_variants, unit_variants);
+        exclusive!(tuple_variants, arg_structs);
+        exclusive!(unit_variants, arg_structs);
+        exclusive!(no_map, dispatch);
+        exclusive!(no_map, into_fn);
+        exclusive!(no_map, map_name);
+        exclusive!(no_map, jump_table);
+        exclusive!(no_map, generate_tests);
+        exclusive!(no_map, wasm);
+        exclusive!(no_map, pyo3);
+        exclusive!(wasm, pyo3);
+        exclusive!(no_map, dispatch_json);
+        exclusive!(no_map, napi);
+        exclusive!(return_as, try_map);
+        exclusive!(context, as_fn);
+        exclusive!(context, map_all);
+        exclusive!(context, dispatch);
+        exclusive!(no_map, dyn_dispatch);
+        exclusive!(no_map, map_batch);
+        exclusive!(context, map_batch);
+        exclusive!(no_map, map_batch_par);
+        exclusive!(context, map_batch_par);
+        exclusive!(no_map, map_batch_concurrent);
+        exclusive!(context, map_batch_concurrent);
+        exclusive!(by_ref, map_batch_concurrent);
+        exclusive!(by_mut, map_batch_concurrent);
+        exclusive!(context, dyn_dispatch);
+        exclusive!(free_fn, no_map);
+        exclusive!(ffi, no_map);
+        exclusive!(box_return, output_enum);
+        exclusive!(return_as, box_return);
+        exclusive!(return_as, output_enum);
+        // Only `map` and the functions like it can call the closure or delegate to the nested `enum`s, so the
+        // variants holding them can't be matched by anything else that is generated for every variant. The closure
+        // also can't be called through a shared reference unless `map` takes one.
+        let extra = [
+            r.nest.as_ref().map(|(nest, _)| nest),
+            r.custom.as_ref().map(|(custom, _)| custom),
+        ];
+        for extra in extra.into_iter().flatten() {
+            let map_ref = r.map_ref.as_ref().filter(|_| extra == "custom");
+            let others = [
+                map_ref,
+                r.display.as_ref(),
+                r.debug.as_ref(),
+                r.strum_like.as_ref(),
+                r.ordering.as_ref(),
+                r.name.as_ref(),
+                r.as_fn.as_ref(),
+                r.jump_table.as_ref(),
+                r.kind.as_ref().map(|(kind, _)| kind),
+                r.match_macro.as_ref().map(|(match_macro, _)| match_macro),
+                r.visitor.as_ref().map(|(visitor, _)| visitor),
+            ];
+            if let Some(other) = others.into_iter().flatten().next() {
+                let message = format!("cannot combine `{}` and `{}`", extra, other);
+                let mut err = syn::Error::new(extra.span(), &message);
+                err.combine(syn::Error::new(other.span(), message));
+                return Err(err);
+            }
+        }
+        exclusive!(try_map, output_enum);
+
+        Ok(r)
+    }
+}
+
+/// Finds the integer type given to a `#[repr(...)]` attribute (if any).
+pub fn repr(attributes: &[Attribute]) -> Option<Ident> {
+    const INTEGERS: [&str; 12] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+
+    let mut r = None;
+    for attribute in attributes
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("repr"))
+    {
+        // Errors are ignored, since the compiler reports them when it processes the attribute itself.
+        let _ = attribute.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                if INTEGERS.contains(&&*ident.to_string()) {
+                    r = Some(ident.clone());
+                }
+            }
+            Ok(())
+        });
+    }
+
+    r
+}
+
+/// Finds the name of the type that the `impl` block is for, which is used as the name of the generated `enum`.
+pub fn enum_ident(self_ty: &Type) -> Ident {
+    match self_ty {
+        Type::Path(TypePath { path, .. }) => match path.segments.last() {
+            Some(segment) => segment.ident.clone(),
+            None => abort!(self_ty, "expected a type name"),
+        },
+        _ => abort!(self_ty, "expected a type name"),
+    }
+}
+
+/// Checks that none of the functions in the `impl` block has the same name as the generated `map` function (if it is
+/// generated) or any of the other functions generated for the `enum`, which would otherwise be reported as a duplicate
+/// definition.
+pub fn check_names(input: &ItemImpl, args: &Args, map: bool) {
+    let functions = input
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(function) => Some(&function.sig),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // When `async` and `const` functions are mixed, `map` is accompanied by `map_async` and `map_const`.
+    let split = map
+        && functions
+            .iter()
+            .any(|signature| signature.asyncness.is_some())
+        && functions
+            .iter()
+            .any(|signature| signature.constness.is_some());
+    let map_ident = args.map_ident();
+    for signature in &functions {
+        if map && signature.ident == map_ident {
+            emit_error!(
+                signature.ident,
+                "`{}` is already generated by the macro attribute, and can be renamed with `map_name = ...`",
+                map_ident
+            );
+        }
+        if split && (signature.ident == "map_async" || signature.ident == "map_const") {
+            emit_error!(
+                signature.ident,
+                "`{}` is already generated by the macro attribute, since some functions are `async` and others are `const`",
+                signature.ident
+            );
+        }
+    }
+
+    // The functions generated by each argument, along with the name of the argument.
+    let mut generated = Vec::new();
+    let mut add = |enabled: bool, arg: &str, names: &[String]| {
+        if enabled {
+            generated.extend(names.iter().map(|name| (name.clone(), arg.to_owned())));
+        }
+    };
+    let names = |prefix: &str| {
+        functions
+            .iter()
+            .map(|signature| format!("{}{}", prefix, generate::name(signature)))
+            .collect::<Vec<_>>()
+    };
+    add(args.map_ref.is_some(), "map_ref", &["map_ref".to_owned()]);
+    add(args.try_map.is_some(), "try_map", &["try_map".to_owned()]);
+    add(
+        args.map_catch.is_some(),
+        "map_catch",
+        &["map_catch".to_owned()],
+    );
+    add(args.timed.is_some(), "timed", &["map_timed".to_owned()]);
+    add(
+        args.map_batch.is_some(),
+        "map_batch",
+        &["map_batch".to_owned()],
+    );
+    add(
+        args.map_batch_par.is_some(),
+        "map_batch_par",
+        &["map_batch_par".to_owned()],
+    );
+    add(
+        args.map_batch_concurrent.is_some(),
+        "map_batch_concurrent",
+        &["map_batch_concurrent".to_owned()],
+    );
+    add(args.into_fn.is_some(), "into_fn", &["into_fn".to_owned()]);
+    add(args.map_all.is_some(), "map_all", &["map_all".to_owned()]);
+    add(
+        args.dispatch_by_name.is_some(),
+        "dispatch_by_name",
+        &["dispatch_by_name".to_owned()],
+    );
+    add(
+        args.registry.is_some(),
+        "registry",
+        &["dispatch_dynamic".to_owned()],
+    );
+    add(
+        args.dispatch_json.is_some(),
+        "dispatch_json",
+        &["dispatch_json".to_owned()],
+    );
+    add(args.variants.is_some(), "variants", &["iter".to_owned()]);
+    add(
+        args.strum_like.is_some(),
+        "strum_like",
+        &["iter".to_owned()],
+    );
+    add(args.count.is_some(), "count", &["len".to_owned()]);
+    add(args.name.is_some(), "name", &["name".to_owned()]);
+    add(args.kind.is_some(), "kind", &["kind".to_owned()]);
+    add(args.visitor.is_some(), "visitor", &["accept".to_owned()]);
+    add(args.as_fn.is_some(), "as_fn", &["as_fn".to_owned()]);
+    add(args.constructors.is_some(), "constructors", &names("new_"));
+    add(args.accessors.is_some(), "accessors", &names("as_"));
+    add(args.accessors.is_some(), "accessors", &names("into_"));
+
+    for signature in &functions {
+        let ident = signature.ident.unraw().to_string();
+        for (name, arg) in &generated {
+            if ident == *name {
+                emit_error!(
+                    signature.ident,
+                    "`{}` is already generated by the `{}` argument",
+                    name,
+                    arg
+                );
+            }
+        }
+    }
+}
+
+/// An `impl` block for the `enum` generated from a module, which is named after the module and contains its free
+/// functions. The attributes of the module are applied to the `enum` instead.
+pub fn module_impl(input: &ItemMod) -> ItemImpl {
+    let Some((_, items)) = &input.content else {
+        abort!(input, "expected a module with a body");
+    };
+    let name = format_ident!("{}", input.ident.unraw().to_string().to_case(Case::Pascal));
+    let attributes = &input.attrs;
+    let functions = items.iter().filter(|item| matches!(item, Item::Fn(_)));
+
+    parse_quote! {
+        #(#attributes)*
+        impl #name {
+            #(#functions)*
+        }
+    }
+}
+
+/// The macro defined by an `impl` block marked with `extend`, which the `impl` block that generates the `enum` calls
+/// (through `extended_by`) to collect its functions. Only the signatures of the functions are needed, so they are given
+/// empty bodies.
+pub fn extension(input: &ItemImpl, name: &Ident) -> TokenStream {
+    let mut block = input.clone();
+    block.attrs.clear();
+    block.items.retain(|item| matches!(item, ImplItem::Fn(_)));
+    for item in &mut block.items {
+        if let ImplItem::Fn(function) = item {
+            for param in &function.sig.generics.params {
+                emit_error!(
+                    param,
+                    "generic functions are not supported in `impl` blocks marked with `extend`"
+                );
+            }
+            function.block = parse_quote!({});
+        }
+    }
+
+    quote! {
+        macro_rules! #name {
+            ($($tokens:tt)*) => {
+                ::enum_from_functions::__private::extend! { #block $($tokens)* }
+            };
+        }
+    }
+}
+
+/// The input passed from one macro defined by an `impl` block marked with `extend` to the next: the block that the
+/// macro was defined by, the macros that are left to call, the blocks collected so far, and the arguments and input of
+/// the macro attribute on the `impl` block that generates the `enum`.
+pub struct Extension {
+    pub block: ItemImpl,
+    pub remaining: Vec<Path>,
+    pub collected: Vec<ItemImpl>,
+    pub args: TokenStream,
+    pub input: TokenStream,
+}
+impl Parse for Extension {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let block = input.parse()?;
+        let remaining;
+        bracketed!(remaining in input);
+        let remaining = Punctuated::<Path, Token![,]>::parse_terminated(&remaining)?;
+        let collected;
+        braced!(collected in input);
+        let collected = {
+            let mut blocks = Vec::new();
+            while !collected.is_empty() {
+                blocks.push(collected.parse()?);
+            }
+            blocks
+        };
+        let args;
+        parenthesized!(args in input);
+
+        Ok(Extension {
+            block,
+            remaining: remaining.into_iter().collect(),
+            collected,
+            args: args.parse()?,
+            input: input.parse()?,
+        })
+    }
+}
+
+/// Calls the first of the macros given to `extended_by`, which passes the input along to the rest of them.
+pub fn extended_by(
+    paths: &[Path],
+    collected: &[ItemImpl],
+    args: &TokenStream,
+    input: &TokenStream,
+) -> TokenStream {
+    let Some((first, rest)) = paths.split_first() else {
+        return TokenStream::new();
+    };
+    quote! {
+        #first! { [#(#rest),*] { #(#collected)* } (#args) #input }
+    }
+}
+
+/// Adds the functions of the `impl` blocks marked with `extend` to the `impl` block that generates the `enum`, after its
+/// own functions. Each block must be for the same type.
+pub fn extend(input: &mut ItemImpl, blocks: Vec<ItemImpl>) {
+    let name = enum_ident(&input.self_ty);
+    for block in blocks {
+        if enum_ident(&block.self_ty) != name {
+            emit_error!(
+                block.self_ty,
+                "expected an `impl` block for `{}`, since it is added to that `enum` by `extended_by`",
+                name
+            );
+            continue;
+        }
+        input.items.extend(block.items);
+    }
+}
+
+/// Replaces the stubs declared for the functions given to `external` (such as `fn read_file(path: String) -> String;`)
+/// with functions that forward to them, so that they are used like the other functions in the `impl` block.
+pub fn external(input: &mut ItemImpl, paths: &[Path]) {
+    let mut found = vec![false; paths.len()];
+    for item in &mut input.items {
+        let ImplItem::Verbatim(tokens) = item else {
+            continue;
+        };
+        let Ok(ForeignItemFn {
+            attrs, vis, sig, ..
+        }) = syn::parse2::<ForeignItemFn>(tokens.clone())
+        else {
+            continue;
+        };
+        let Some(index) = paths.iter().position(|path| {
+            path.segments
+                .last()
+                .is_some_and(|segment| segment.ident == sig.ident)
+        }) else {
+            continue;
+        };
+        found[index] = true;
+
+        let mut arguments = Vec::new();
+        for input in &sig.inputs {
+            match input {
+                FnArg::Typed(PatType { pat, .. }) => match &**pat {
+                    Pat::Ident(PatIdent {
+                        ident,
+                        by_ref: None,
+                        subpat: None,
+                        ..
+                    }) => arguments.push(ident),
+                    pat => emit_error!(pat, "expected a parameter name"),
+                },
+                FnArg::Receiver(receiver) => {
+                    emit_error!(receiver, "functions given to `external` cannot take `self`")
+                }
+            }
+        }
+        let path = &paths[index];
+        let call = match sig.asyncness {
+            Some(_) => quote!(#path(#(#arguments),*).await),
+            None => quote!(#path(#(#arguments),*)),
+        };
+        *item = parse_quote! {
+            #(#attrs)*
+            #[inline]
+            #vis #sig {
+                #call
+            }
+        };
+    }
+
+    for (path, found) in paths.iter().zip(found) {
+        if !found {
+            emit_error!(
+                path,
+                "expected a signature to be declared for `{}` in the `impl` block, such as `fn {}(...) -> ...;`",
+                quote!(#path).to_string().replace(' ', ""),
+                path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default()
+            );
+        }
+    }
+}
+
+/// With `maybe_async` and the `is_sync` feature, makes a function blocking by removing `async` from it and `.await`
+/// from the calls in its body, in the same way as the `maybe-async` crate. Nested `async` blocks, closures and items are
+/// left as they are, since they are still `async` (and so is anything that is awaited other than a call).
+pub fn make_sync(signature: &mut Signature, block: &mut Block) {
+    struct RemoveAwait;
+    impl VisitMut for RemoveAwait {
+        fn visit_expr_mut(&mut self, expr: &mut Expr) {
+            match expr {
+                Expr::Async(_) | Expr::Closure(_) => {}
+                Expr::Await(ExprAwait { base, .. })
+                    if matches!(&**base, Expr::Call(_) | Expr::MethodCall(_)) =>
+                {
+                    self.visit_expr_mut(base);
+                    *expr = std::mem::replace(
+                        &mut **base,
+                        Expr::Verbatim(proc_macro2::TokenStream::new()),
+                    );
+                }
+                expr => visit_mut::visit_expr_mut(self, expr),
+            }
+        }
+
+        fn visit_item_mut(&mut self, _: &mut Item) {}
+    }
+
+    signature.asyncness = None;
+    RemoveAwait.visit_block_mut(block);
+}
+
+/// Makes every function in the `impl` block blocking (see [`make_sync`]).
+pub fn make_sync_impl(input: &mut ItemImpl) {
+    for item in &mut input.items {
+        if let ImplItem::Fn(function) = item {
+            make_sync(&mut function.sig, &mut function.block);
+        }
+    }
+}
+
+/// With `clap`, the `#[arg(...)]` attributes of the parameters are meant for `clap`, so they are wrapped in a
+/// `#[cfg_attr(all(), ...)]` attribute (which is expanded before `clap` sees it) to forward them to the fields as they
+/// are. The arguments of the macro can still be given to a parameter through `#[enum_from_functions(...)]`.
+pub fn clap_arguments(input: &mut ItemImpl) {
+    for item in &mut input.items {
+        let ImplItem::Fn(function) = item else {
+            continue;
+        };
+        for input in &mut function.sig.inputs {
+            let FnArg::Typed(PatType { attrs, .. }) = input else {
+                continue;
+            };
+            for attribute in attrs {
+                if let Meta::List(list) = &attribute.meta {
+                    if list.path.is_ident("arg") {
+                        attribute.meta = parse_quote!(cfg_attr(all(), #list));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Removes the context parameter given to `context` from every function, so that it isn't stored in the variants.
+/// `map` takes it instead, and passes it to the function as its first parameter (after any receiver).
+pub fn strip_context(input: &mut ItemImpl, context: &Type) {
+    for item in &mut input.items {
+        let ImplItem::Fn(function) = item else {
+            continue;
+        };
+        let index = usize::from(matches!(
+            function.sig.inputs.first(),
+            Some(FnArg::Receiver(_))
+        ));
+        match function.sig.inputs.iter().nth(index) {
+            Some(FnArg::Typed(PatType { ty, .. })) if **ty == *context => (),
+            Some(input) => {
+                emit_error!(
+                    input,
+                    "expected the context parameter of type `{}`",
+                    generate::pretty(quote!(#context))
+                );
+                continue;
+            }
+            None => {
+                emit_error!(
+                    function.sig.paren_token.span.join(),
+                    "expected the context parameter of type `{}`",
+                    generate::pretty(quote!(#context))
+                );
+                continue;
+            }
+        }
+        function.sig.inputs = std::mem::take(&mut function.sig.inputs)
+            .into_pairs()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, pair)| pair)
+            .collect();
+    }
+}
+
+/// Moves the type and lifetime parameters of every function onto the `impl` block (and so onto the `enum`), since the
+/// variants need to be able to name them. The bounds in the `where` clauses of the functions are moved as well, since
+/// `map` needs them in order to call the functions.
+///
+/// The signatures of the functions in a trait `impl` block have to match the trait, so their parameters can't be moved
+/// and their bounds are copied instead.
+pub fn lift_generics(input: &mut ItemImpl) {
+    let trait_impl = input.trait_.is_some();
+    let mut lifted = Vec::<GenericParam>::new();
+    let mut predicates = Vec::<WherePredicate>::new();
+    for item in &mut input.items {
+        let ImplItem::Fn(function) = item else {
+            continue;
+        };
+        // Const parameters are only moved if the parameters of the function use them (such as `[u8; N]`), since the
+        // others can only be provided to the function explicitly.
+        let inputs = function
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                FnArg::Typed(PatType { ty, .. }) => Some(ty),
+                FnArg::Receiver(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let inputs = quote!(#(#inputs)*);
+        let kept = |param: &GenericParam| match param {
+            GenericParam::Const(param) => !generate::mentions(inputs.clone(), &param.ident),
+            _ => false,
+        };
+        let generics = &mut function.sig.generics;
+        if trait_impl {
+            for param in &generics.params {
+                if !kept(param) {
+                    emit_error!(
+                        param,
+                        "generic functions are not supported in trait `impl` blocks"
+                    );
+                }
+            }
+        } else {
+            let (consts, params) = std::mem::take(&mut generics.params)
+                .into_iter()
+                .partition::<Vec<_>, _>(kept);
+            generics.params.extend(consts);
+            if generics.params.is_empty() {
+                generics.lt_token = None;
+                generics.gt_token = None;
+            }
+            lifted.extend(params);
+        }
+
+        // Bounds that refer to the parameters left on the function can't be moved.
+        if let Some(where_clause) = &mut generics.where_clause {
+            let movable = |predicate: &WherePredicate| {
+                !generics.params.iter().any(|param| match param {
+                    GenericParam::Const(param) => {
+                        generate::mentions(quote!(#predicate), &param.ident)
+                    }
+                    _ => false,
+                })
+            };
+            predicates.extend(
+                where_clause
+                    .predicates
+                    .iter()
+                    .filter(|predicate| movable(predicate))
+                    .cloned(),
+            );
+            if !trait_impl {
+                where_clause.predicates = std::mem::take(&mut where_clause.predicates)
+                    .into_iter()
+                    .filter(|predicate| !movable(predicate))
+                    .collect();
+                if where_clause.predicates.is_empty() {
+                    generics.where_clause = None;
+                }
+            }
+        }
+    }
+
+    for param in lifted {
+        push_generic(input, param);
+    }
+    if !predicates.is_empty() {
+        input
+            .generics
+            .make_where_clause()
+            .predicates
+            .extend(predicates);
+    }
+}
+
+/// Adds a generic parameter to the `impl` block and to the type of the `enum`, unless a parameter with the same name
+/// has already been declared.
+pub fn push_generic(input: &mut ItemImpl, param: GenericParam) {
+    let name = |param: &GenericParam| match param {
+        GenericParam::Type(param) => param.ident.to_string(),
+        GenericParam::Lifetime(param) => param.lifetime.to_string(),
+        GenericParam::Const(param) => param.ident.to_string(),
+    };
+    if input
+        .generics
+        .params
+        .iter()
+        .any(|existing| name(existing) == name(&param))
+    {
+        emit_error!(
+            param,
+            "`{}` is already declared by the `impl` block or another function",
+            name(&param)
+        );
+        return;
+    }
+
+    let Type::Path(TypePath { path, .. }) = &mut *input.self_ty else {
+        return;
+    };
+    let Some(segment) = path.segments.last_mut() else {
+        return;
+    };
+    if segment.arguments.is_none() {
+        segment.arguments = PathArguments::AngleBracketed(parse_quote!(<>));
+    }
+    let PathArguments::AngleBracketed(arguments) = &mut segment.arguments else {
+        return;
+    };
+
+    // Lifetimes have to be declared before any other generic parameters.
+    match &param {
+        GenericParam::Lifetime(lifetime) => {
+            let argument = GenericArgument::Lifetime(lifetime.lifetime.clone());
+            let index = input.generics.lifetimes().count();
+            input.generics.params.insert(index, param);
+            let index = arguments
+                .args
+                .iter()
+                .take_while(|argument| matches!(argument, GenericArgument::Lifetime(_)))
+                .count();
+            arguments.args.insert(index, argument);
+        }
+        GenericParam::Type(TypeParam { ident, .. })
+        | GenericParam::Const(ConstParam { ident, .. }) => {
+            arguments.args.push(parse_quote!(#ident));
+            input.generics.params.push(param);
+        }
+    }
+    if input.generics.lt_token.is_none() {
+        input.generics.lt_token = Some(Default::default());
+        input.generics.gt_token = Some(Default::default());
+    }
+}
+
+/// Arguments provided to the macro attribute on an individual function inside of the `impl` block.
+#[derive(Default)]
+pub struct FunctionArgs {
+    pub discriminant: Option<Expr>,
+    pub default: Option<Ident>,
+    /// Stores the parameters of the function in boxes, so that a large parameter doesn't increase the size of every
+    /// variant.
+    pub boxed: Option<Ident>,
+    /// The `#[cfg(...)]` attributes on the function, which are applied to everything generated for it.
+    pub cfgs: Vec<Attribute>,
+    /// The doc comments of the function, which become the help text of its subcommand with `clap`.
+    pub docs: Vec<Attribute>,
+    /// The arguments provided to each parameter of the function (other than `self`).
+    pub parameters: Vec<ParameterArgs>,
+}
+impl FunctionArgs {
+    pub fn is_attribute(attribute: &Attribute) -> bool {
+        attribute.path().is_ident("enum_from_functions")
+    }
+
+    /// Removes the macro attribute from every function in the `impl` block, so that it isn't present in the output.
+    pub fn strip(input: &mut ItemImpl) {
+        for item in &mut input.items {
+            if let ImplItem::Fn(function) = item {
+                function
+                    .attrs
+                    .retain(|attribute| !FunctionArgs::is_attribute(attribute));
+            }
+        }
+    }
+}
+impl Parse for FunctionArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut r = FunctionArgs::default();
+
+        while !input.is_empty() {
+            let ident = input.call(Ident::parse_any)?;
+            match ident.to_string().as_str() {
+                "discriminant" => r.discriminant = Some(value(input)?),
+                "default" => r.default = Some(ident),
+                "boxed" => r.boxed = Some(ident),
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("unknown argument `{}`", ident),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(r)
+    }
+}
+impl TryFrom<&[Attribute]> for FunctionArgs {
+    type Error = syn::Error;
+
+    fn try_from(attributes: &[Attribute]) -> Result<Self, Self::Error> {
+        let mut attributes = attributes
+            .iter()
+            .filter(|attribute| FunctionArgs::is_attribute(attribute));
+        let Some(attribute) = attributes.next() else {
+            return Ok(FunctionArgs::default());
+        };
+        if let Some(duplicate) = attributes.next() {
+            return Err(syn::Error::new(
+                duplicate.span(),
+                "the macro attribute can only be applied once per function",
+            ));
+        }
+
+        attribute.parse_args()
+    }
+}
+
+/// Arguments provided through `#[arg(...)]` (or the macro attribute) on an individual parameter of a function.
+#[derive(Default)]
+pub struct ParameterArgs {
+    pub default: Option<Expr>,
+    /// The name of the field that stores the parameter, if it isn't named after the parameter.
+    pub field: Option<Ident>,
+}
+impl ParameterArgs {
+    pub fn is_attribute(attribute: &Attribute) -> bool {
+        attribute.path().is_ident("arg") || FunctionArgs::is_attribute(attribute)
+    }
+
+    /// Replaces the patterns of the parameters given a `field` with the name of that field, so that the variants and
+    /// the other generated items use it in place of the name of the parameter. Errors are reported when the arguments
+    /// are extracted instead.
+    pub fn rename_fields(input: &mut ItemImpl) {
+        for item in &mut input.items {
+            let ImplItem::Fn(function) = item else {
+                continue;
+            };
+            for input in &mut function.sig.inputs {
+                let FnArg::Typed(PatType { attrs, pat, .. }) = input else {
+                    continue;
+                };
+                if let Ok(ParameterArgs {
+                    field: Some(field), ..
+                }) = ParameterArgs::try_from(&**attrs)
+                {
+                    *pat = parse_quote!(#field);
+                }
+            }
+        }
+    }
+}
+impl Parse for ParameterArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut r = ParameterArgs::default();
+
+        while !input.is_empty() {
+            let ident = input.call(Ident::parse_any)?;
+            match ident.to_string().as_str() {
+                "default" => r.default = Some(value(input)?),
+                "field" => {
+                    let field: LitStr = value(input)?;
+                    r.field = Some(field.parse().map_err(|_| {
+                        syn::Error::new(field.span(), "expected the name of a field")
+                    })?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("unknown argument `{}`", ident),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(r)
+    }
+}
+impl TryFrom<&[Attribute]> for ParameterArgs {
+    type Error = syn::Error;
+
+    fn try_from(attributes: &[Attribute]) -> Result<Self, Self::Error> {
+        let mut attributes = attributes
+            .iter()
+            .filter(|attribute| ParameterArgs::is_attribute(attribute));
+        let Some(attribute) = attributes.next() else {
+            return Ok(ParameterArgs::default());
+        };
+        if let Some(duplicate) = attributes.next() {
+            return Err(syn::Error::new(
+                duplicate.span(),
+                "`#[arg(...)]` (or the macro attribute) can only be applied once per parameter",
+            ));
+        }
+
+        attribute.parse_args()
+    }
+}
+
+pub struct Functions<'a> {
+    /// The type of the `enum`.
+    pub self_ty: &'a Type,
+    pub generics: &'a Generics,
+    /// The trait implemented by the `impl` block, if any.
+    pub trait_: Option<&'a Path>,
+    /// Whether the functions are the free functions of a module, rather than those of an `impl` block.
+    pub free: bool,
+    pub signatures: Vec<&'a Signature>,
+    pub args: Vec<FunctionArgs>,
+    pub return_type: ReturnType,
+    pub asyncness: Option<Token![async]>,
+    pub constness: Option<Token![const]>,
+    pub unsafety: Option<Token![unsafe]>,
+}
+impl<'a> Functions<'a> {
+    fn new(self_ty: &'a Type, generics: &'a Generics) -> Self {
+        Functions {
+            self_ty,
+            generics,
+            trait_: None,
+            free: false,
+            signatures: Vec::new(),
+            args: Vec::new(),
+            return_type: ReturnType::Default,
+            asyncness: None,
+            constness: None,
+            unsafety: None,
+        }
+    }
+
+    /// The `const` keyword of `map`, which is left out if any function is `async` since `map` can't be both (the
+    /// `const` functions can then be called from `map_const` instead). It is also left out if `map` borrows the `enum`
+    /// and clones any of its fields, since `Clone::clone` can't be called from a `const fn`.
+    pub fn map_constness(&self, args: &Args) -> Option<Token![const]> {
+        self.constness
+            .filter(|_| self.asyncness.is_none())
+            .filter(|_| args.borrow().is_none() || !self.clones_fields(args))
+    }
+
+    /// Checks whether `map` has to clone anything out of the `enum` when it borrows it: the receivers of functions
+    /// taking `self` by value, and the fields other than those passed on as mutable references with `by_mut`.
+    fn clones_fields(&self, args: &Args) -> bool {
+        self.signatures.iter().any(|signature| {
+            signature.inputs.iter().any(|input| match input {
+                FnArg::Receiver(receiver) => receiver.reference.is_none(),
+                FnArg::Typed(_) if args.unit_variants.is_some() => false,
+                FnArg::Typed(PatType { ty, .. }) => !matches!(
+                    &**ty,
+                    Type::Reference(TypeReference { mutability: Some(_), .. }) if args.by_mut.is_some()
+                ),
+            })
+        })
+    }
+
+    /// The `unsafe` keyword of `map`, which is left out with `assume_safe` (in which case the calls to the `unsafe`
+    /// functions are wrapped in `unsafe` blocks instead).
+    pub fn map_unsafety(&self, args: &Args) -> Option<Token![unsafe]> {
+        self.unsafety.filter(|_| args.assume_safe.is_none())
+    }
+
+    /// Checks whether every function has the same return type, without reporting any errors.
+    pub fn return_types_match(&self) -> bool {
+        self.signatures
+            .windows(2)
+            .all(|pair| pair[0].output == pair[1].output)
+    }
+
+    /// Checks that every function has the same return type, so that it can be returned by `map`.
+    pub fn check_return_types(&self) {
+        let mut signatures = self.signatures.iter();
+        let Some(first) = signatures.next() else {
+            return;
+        };
+        // The return types are shown as they would be written, with `()` for functions that don't declare one (in
+        // which case the error points at the name of the function instead).
+        let pretty = |output: &ReturnType| match output {
+            ReturnType::Default => String::from("()"),
+            ReturnType::Type(_, ty) => generate::pretty(quote!(#ty)),
+        };
+        let span = |signature: &Signature| match &signature.output {
+            ReturnType::Default => signature.ident.to_token_stream(),
+            ReturnType::Type(_, ty) => ty.to_token_stream(),
+        };
+        for signature in signatures {
+            if first.output != signature.output {
+                emit_error!(
+                    span(first),
+                    "expected `{}` (the return type of `{}`), found `{}`",
+                    pretty(&signature.output),
+                    signature.ident,
+                    pretty(&first.output)
+                );
+                emit_error!(
+                    span(signature),
+                    "expected `{}` (the return type of `{}`), found `{}`",
+                    pretty(&first.output),
+                    first.ident,
+                    pretty(&signature.output)
+                );
+            }
+        }
+    }
+
+    /// Checks that every function takes the same parameters (not including receivers), and returns the parameters of
+    /// the first function.
+    pub fn shared_inputs(&self) -> Result<Vec<&'a PatType>, syn::Error> {
+        fn typed(signature: &Signature) -> Vec<&PatType> {
+            signature
+                .inputs
+                .iter()
+                .filter_map(|input| match input {
+                    FnArg::Typed(pat_type) => Some(pat_type),
+                    FnArg::Receiver(_) => None,
+                })
+                .collect()
+        }
+
+        let mut signatures = self.signatures.iter();
+        let Some(first) = signatures.next() else {
+            return Ok(Vec::new());
+        };
+        let shared = typed(first);
+
+        for signature in signatures {
+            let inputs = typed(signature);
+            if inputs.len() != shared.len()
+                || inputs
+                    .iter()
+                    .zip(&shared)
+                    .any(|(input, shared)| input.ty != shared.ty)
+            {
+                let mut err = syn::Error::new(
+                    signature.paren_token.span.join(),
+                    format!("parameters do not match those of `{}`", first.ident),
+                );
+                err.combine(syn::Error::new(
+                    first.paren_token.span.join(),
+                    format!("parameters do not match those of `{}`", signature.ident),
+                ));
+                return Err(err);
+            }
+        }
+
+        Ok(shared)
+    }
+}
+impl<'a> TryFrom<&'a ItemImpl> for Functions<'a> {
+    type Error = syn::Error;
+
+    fn try_from(input: &'a ItemImpl) -> Result<Self, Self::Error> {
+        let mut r = Functions::new(&input.self_ty, &input.generics);
+        r.trait_ = input.trait_.as_ref().map(|(_, path, _)| path);
+
+        // This will be set once the first function is found.
+        let mut return_type: Option<&ReturnType> = None;
+
+        // Iterate over all items in the `input` block.
+        for item in &input.items {
+            // Only process the item if it is a function.
+            if let ImplItem::Fn(function) = item {
+                if return_type.is_none() {
+                    return_type = Some(&function.sig.output);
+                }
+
+                // Add the function signature to the list and set the modifier flags on the return `struct` (if
+                // necessary).
+                r.signatures.push(&function.sig);
+                let mut args = match FunctionArgs::try_from(&*function.attrs) {
+                    Ok(args) => args,
+                    Err(err) => {
+                        emit_error!(err.span(), err);
+                        FunctionArgs::default()
+                    }
+                };
+                args.cfgs = function
+                    .attrs
+                    .iter()
+                    .filter(|attribute| attribute.path().is_ident("cfg"))
+                    .cloned()
+                    .collect();
+                args.docs = function
+                    .attrs
+                    .iter()
+                    .filter(|attribute| attribute.path().is_ident("doc"))
+                    .cloned()
+                    .collect();
+                args.parameters = function
+                    .sig
+                    .inputs
+                    .iter()
+                    .filter_map(|input| match input {
+                        FnArg::Typed(pat_type) => Some(pat_type),
+                        FnArg::Receiver(_) => None,
+                    })
+                    .map(|pat_type| {
+                        // A parameter that is configured out would have to be left out of everything generated for
+                        // the variant (its bindings, the call, the constructors and so on), so it isn't supported.
+                        for attribute in &pat_type.attrs {
+                            if attribute.path().is_ident("cfg") {
+                                emit_error!(
+                                    attribute,
+                                    "`#[cfg(...)]` is not supported on parameters"
+                                );
+                            }
+                        }
+                        ParameterArgs::try_from(&*pat_type.attrs).unwrap_or_else(|err| {
+                            emit_error!(err.span(), err);
+                            ParameterArgs::default()
+                        })
+                    })
+                    .collect();
+                r.args.push(args);
+                macro_rules! set_flag {
+                    ( $( $flag:ident ),* ) => {
+                        $(
+                            if let Some($flag) = function.sig.$flag {
+                                r.$flag = Some($flag.clone());
+                            }
+                        )*
+                    };
+                }
+                set_flag!(asyncness, constness, unsafety);
+            }
+        }
+
+        if let Some(return_type) = return_type {
+            r.return_type = return_type.clone();
+        }
+
+        Ok(r)
+    }
+}
@@ -0,0 +1,3472 @@
+use convert_case::{Case, Casing};
+use proc_macro::Span;
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use proc_macro_error::emit_error;
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    ext::IdentExt,
+    parse_quote,
+    punctuated::{Pair, Punctuated},
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+    Attribute, Block, Expr, Field, Fields, FnArg, GenericArgument, GenericParam, Generics, Ident,
+    ImplItem, Item, ItemImpl, ItemMod, ItemStruct, LitStr, Meta, Pat, PatIdent, PatType, Path,
+    PathArguments, PathSegment, Receiver, ReturnType, Signature, Token, Type, TypeImplTrait,
+    TypePath, TypeReference, Variant, Visibility,
+};
+
+use crate::extract::{self, Args, FunctionArgs, Functions, ParameterArgs};
+
+/// The variants of the `enum`, along with the argument `struct` that each variant wraps (if any), and a hidden variant
+/// holding any generic parameters that aren't used by the other variants.
+pub struct Variants(
+    pub Vec<Variant>,
+    pub Vec<Option<ItemStruct>>,
+    pub Option<Variant>,
+);
+impl Variants {
+    pub fn new(functions: &Functions<'_>, args: &Args, attributes: &[Attribute]) -> Self {
+        if let (Some(arg_structs), false) =
+            (&args.arg_structs, functions.generics.params.is_empty())
+        {
+            emit_error!(
+                arg_structs,
+                "`{}` is not supported for generic `impl` blocks",
+                arg_structs
+            );
+        }
+
+        let (variants, structs): (Vec<_>, _) = functions
+            .signatures
+            .iter()
+            .zip(&functions.args)
+            .map(|(signature, function_args)| {
+                Variants::convert_single(signature, function_args, args, attributes, functions)
+            })
+            .unzip();
+
+        // Different functions can be converted into the same variant name (such as `foo_bar` and `fooBar`). Functions with
+        // the same name are reported by the compiler instead, and may not collide if they are both behind a `#[cfg]`.
+        let cfgs = |index: usize| !functions.args[index].cfgs.is_empty();
+        for (index, (variant, signature)) in variants.iter().zip(&functions.signatures).enumerate()
+        {
+            for (other, (other_variant, other_signature)) in variants
+                .iter()
+                .zip(&functions.signatures)
+                .take(index)
+                .enumerate()
+            {
+                if variant.ident != other_variant.ident
+                    || signature.ident == other_signature.ident
+                    || (cfgs(index) && cfgs(other))
+                {
+                    continue;
+                }
+                for (signature, other_signature) in
+                    [(signature, other_signature), (other_signature, signature)]
+                {
+                    emit_error!(
+                        signature.ident,
+                        "`{}` and `{}` both correspond to the variant `{}`, so one of them must be renamed",
+                        signature.ident,
+                        other_signature.ident,
+                        variant.ident
+                    );
+                }
+            }
+        }
+
+        // Every type and lifetime parameter has to be used by the `enum`, so any that aren't used by the fields of the
+        // variants are held by an uninhabited variant instead.
+        let fields = variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .map(|field| &field.ty)
+            .collect::<Vec<_>>();
+        let fields = quote!(#(#fields)*);
+        let mut unused = functions
+            .generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                GenericParam::Type(param) if !mentions(fields.clone(), &param.ident) => {
+                    let ident = &param.ident;
+                    Some(quote!(#ident))
+                }
+                GenericParam::Lifetime(param)
+                    if !mentions(fields.clone(), &param.lifetime.ident) =>
+                {
+                    let lifetime = &param.lifetime;
+                    Some(quote!(&#lifetime ()))
+                }
+                _ => None,
+            })
+            .peekable();
+        let phantom = unused.peek().is_some().then(|| {
+            parse_quote! {
+                #[doc(hidden)]
+                __Phantom(::core::convert::Infallible, ::core::marker::PhantomData<(#(#unused,)*)>)
+            }
+        });
+
+        Self(variants, structs, phantom)
+    }
+
+    fn convert_single(
+        signature: &Signature,
+        function_args: &FunctionArgs,
+        args: &Args,
+        attributes: &[Attribute],
+        functions: &Functions<'_>,
+    ) -> (Variant, Option<ItemStruct>) {
+        let enum_name = functions.self_ty;
+        let variant_name = variant_ident(signature, args);
+        let mut structure = None;
+        let fields: Option<Fields> = {
+            // Fieldless variants take their parameters through `map` instead.
+            if !signature.inputs.is_empty() && args.unit_variants.is_none() {
+                let inputs = typed_inputs(&signature.inputs)
+                    .enumerate()
+                    .map(|(index, PatType { attrs, pat, ty, .. })| {
+                        // When `map` has mutable access to the `enum`, `&mut` parameters are stored by value and
+                        // borrowed from the variant when the function is called. The same goes for shared
+                        // references without a lifetime, which couldn't be stored in the variant otherwise
+                        // (unless the `lifetime` argument is provided to give them one). Parameters of the type of
+                        // the `enum` itself (or references to it that are stored by value, and `Option`s of it) are
+                        // boxed, since the `enum` would have an infinite size otherwise.
+                        let boxed =
+                            function_args.boxed.is_some() && !boxed_self(ty, enum_name, args);
+                        let ty: Type = match (&args.by_mut, &**ty) {
+                            (_, ty) if boxed_self(ty, enum_name, args) => {
+                                parse_quote!(::enum_from_functions::__private::Box<#enum_name>)
+                            }
+                            (_, ty) if optional_self(ty, enum_name) => {
+                                parse_quote!(::core::option::Option<::enum_from_functions::__private::Box<#enum_name>>)
+                            }
+                            (
+                                Some(_),
+                                Type::Reference(TypeReference {
+                                    mutability: Some(_),
+                                    elem,
+                                    ..
+                                }),
+                            ) => (**elem).clone(),
+                            (_, Type::Reference(reference))
+                                if args.lifetime.is_some() && elided(reference) =>
+                            {
+                                Type::Reference(TypeReference {
+                                    lifetime: args.lifetime(),
+                                    ..reference.clone()
+                                })
+                            }
+                            (_, ty) => match borrowed(ty) {
+                                Some(elem) => {
+                                    parse_quote!(<#elem as ::enum_from_functions::__private::ToOwned>::Owned)
+                                }
+                                None => ty.clone(),
+                            },
+                        };
+                        // Functions marked `boxed` store each of their parameters in a box, keeping the `enum` small.
+                        let mut ty = match boxed {
+                            true => parse_quote!(::enum_from_functions::__private::Box<#ty>),
+                            false => ty,
+                        };
+                        replace_self(&mut ty, enum_name);
+                        // The arguments of the parameter aren't forwarded to the field.
+                        let attrs = attrs
+                            .iter()
+                            .filter(|attribute| !ParameterArgs::is_attribute(attribute))
+                            .collect::<Vec<_>>();
+                        (attrs, parameter_name(&signature.inputs, index, pat), ty)
+                    })
+                    .collect::<Vec<_>>();
+                let named = inputs
+                    .iter()
+                    .map(|(attrs, name, ty)| quote!(#(#attrs)* #name: #ty));
+
+                if args.tuple_variants.is_some() {
+                    let unnamed = inputs.iter().map(|(attrs, _, ty)| quote!(#(#attrs)* #ty));
+                    Some(Fields::Unnamed(parse_quote!(( #(#unnamed),* ))))
+                } else if args.arg_structs.is_some() && !inputs.is_empty() {
+                    // The fields are moved into a `struct` of their own, which is wrapped by the variant.
+                    let name = format_ident!("{}Args", variant_name);
+                    let vis = args.nested_enum_vis().unwrap_or(Visibility::Inherited);
+                    let fields = inputs
+                        .iter()
+                        .map(|(attrs, name, ty)| quote!(#(#attrs)* #vis #name: #ty));
+                    let derives = attributes
+                        .iter()
+                        .filter(|attribute| attribute.path().is_ident("derive"));
+                    let cfgs = &function_args.cfgs;
+                    structure = Some(parse_quote! {
+                        #(#cfgs)*
+                        #(#derives)*
+                        #vis struct #name {
+                            #(#fields),*
+                        }
+                    });
+                    Some(Fields::Unnamed(parse_quote!((#name))))
+                } else {
+                    Some(Fields::Named(parse_quote!({ #(#named),* })))
+                }
+            } else {
+                None
+            }
+        };
+
+        let discriminant = function_args
+            .discriminant
+            .as_ref()
+            .map(|discriminant| quote!(= #discriminant));
+
+        let cfgs = &function_args.cfgs;
+        let docs = args
+            .clap
+            .as_ref()
+            .map(|_| &function_args.docs)
+            .into_iter()
+            .flatten();
+
+        (
+            parse_quote!(#(#cfgs)* #(#docs)* #variant_name #fields #discriminant),
+            structure,
+        )
+    }
+
+    /// The types of the values stored in a variant, looking through its argument `struct` (if any).
+    pub fn field_types(&self, index: usize) -> impl Iterator<Item = &Type> {
+        match &self.1[index] {
+            Some(structure) => structure.fields.iter(),
+            None => self.0[index].fields.iter(),
+        }
+        .map(|field| &field.ty)
+    }
+}
+
+/// The name of the variant that corresponds to a function.
+pub fn variant_ident(signature: &Signature, args: &Args) -> Ident {
+    Ident::new(
+        &format!(
+            "{}{}{}",
+            args.prefix.as_ref().map(LitStr::value).unwrap_or_default(),
+            signature.ident.unraw().to_string().to_case(Case::Pascal),
+            args.suffix.as_ref().map(LitStr::value).unwrap_or_default(),
+        ),
+        Span::call_site().into(),
+    )
+}
+
+/// The path that the functions are called through, which goes through the trait for a trait `impl` block so that the
+/// trait doesn't need to be in scope. Free functions are called through the module that they are defined in.
+fn callee(functions: &Functions<'_>) -> TokenStream {
+    match functions.trait_ {
+        _ if functions.free => quote!(self),
+        Some(path) => quote!(<Self as #path>),
+        None => quote!(Self),
+    }
+}
+
+/// Additional parameters of `map`, which are the context given to `context` (if any) followed by the parameters
+/// forwarded to every function when the variants are fieldless.
+pub struct Parameters(pub Vec<PatType>);
+impl Parameters {
+    pub fn new(functions: &Functions<'_>, args: &Args) -> Self {
+        let mut r = Self(Parameters::context(args).into_iter().collect());
+        r.0.extend(Parameters::shared(functions, args).0);
+        r
+    }
+
+    /// The parameter of `map` that receives the context, which is passed to every function before its other
+    /// parameters. It is named so that it can't be shadowed by the fields of the variants.
+    pub fn context(args: &Args) -> Option<PatType> {
+        args.context
+            .as_ref()
+            .map(|context| parse_quote!(__context: #context))
+    }
+
+    /// The parameters forwarded to every function after their other parameters, when the variants are fieldless.
+    fn shared(functions: &Functions<'_>, args: &Args) -> Self {
+        let Some(signature) = functions
+            .signatures
+            .first()
+            .filter(|_| args.unit_variants.is_some())
+        else {
+            return Self(Vec::new());
+        };
+
+        Self::of(signature)
+    }
+
+    /// Parameters named after those of a function, unless they are bound to a pattern.
+    pub fn of(signature: &Signature) -> Self {
+        Self(
+            typed_inputs(&signature.inputs)
+                .enumerate()
+                .map(|(index, PatType { pat, ty, .. })| {
+                    let name = parameter_name(&signature.inputs, index, pat);
+                    parse_quote!(#name: #ty)
+                })
+                .collect(),
+        )
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &Pat> {
+        self.0.iter().map(|pat_type| &*pat_type.pat)
+    }
+}
+
+pub struct Calls(pub Vec<Expr>);
+impl Calls {
+    pub fn new(functions: &Functions<'_>, args: &Args) -> Self {
+        let parameters = Parameters::shared(functions, args);
+        Self(
+            functions
+                .signatures
+                .iter()
+                .zip(&functions.args)
+                .map(|(signature, function_args)| {
+                    let call = Calls::convert_single(
+                        signature,
+                        function_args,
+                        args,
+                        &parameters,
+                        functions,
+                    );
+                    wrap_call(call, signature, &functions.return_type, args)
+                })
+                .collect(),
+        )
+    }
+
+    fn convert_single(
+        signature: &Signature,
+        function_args: &FunctionArgs,
+        args: &Args,
+        parameters: &Parameters,
+        functions: &Functions<'_>,
+    ) -> Expr {
+        let callee = callee(functions);
+        let name = &signature.ident;
+        let recv = if let Some(FnArg::Receiver(receiver)) = signature.inputs.first() {
+            // If `map` borrows the `enum`, `self` is already a reference, so it is either passed through as-is or
+            // cloned (depending on what the function expects).
+            let recv = match (args.borrow(), &receiver.reference, &receiver.mutability) {
+                (None, _, _) => quote!(#receiver),
+                (Some(_), Some(_), None) => quote!(self),
+                (Some(_), Some(_), Some(_)) if args.by_mut.is_some() => quote!(self),
+                (Some(by_ref), Some(_), Some(_)) => {
+                    emit_error!(
+                        receiver,
+                        "functions taking `&mut self` cannot be called from `map(&self)`"
+                    );
+                    emit_error!(by_ref, "`map` takes `&self` because of this argument");
+                    quote!(self)
+                }
+                (Some(_), None, _) => quote!(::core::clone::Clone::clone(self)),
+            };
+            Some(quote!(#recv,))
+        } else {
+            None
+        };
+        // With `by_mut`, the fields are bound to mutable references into the `enum`, which can't be alive while `self`
+        // is passed to the function as well. The fields are therefore cloned first, and then passed in the same way as
+        // when `map` consumes the `enum`.
+        let separate = args.by_mut.is_some()
+            && matches!(
+                signature.inputs.first(),
+                Some(FnArg::Receiver(Receiver {
+                    reference: Some(_),
+                    ..
+                }))
+            );
+        let borrow = args.borrow().filter(|_| !separate);
+        let mut clones = Vec::new();
+        let inputs = typed_inputs(&signature.inputs)
+            .enumerate()
+            .filter(|_| args.unit_variants.is_none())
+            .map(|(index, PatType { pat, ty, .. })| {
+                let ident = parameter_name(&signature.inputs, index, pat);
+                if separate {
+                    if let Type::Reference(TypeReference {
+                        mutability: Some(_),
+                        ..
+                    }) = &**ty
+                    {
+                        emit_error!(
+                            ty,
+                            "functions taking `self` by reference cannot have parameters of a `&mut` type with `by_mut`"
+                        );
+                    }
+                    clones.push(quote!(let #ident = ::core::clone::Clone::clone(#ident);));
+                }
+                // The parameters of functions marked `boxed` are moved out of (or borrowed from) their boxes, and then
+                // passed in the same way as any other parameter.
+                let ident = match (&function_args.boxed, borrow, &**ty) {
+                    (Some(_), _, ty) if boxed_self(ty, functions.self_ty, args) => quote!(#ident),
+                    (Some(_), None, _) => quote!((*#ident)),
+                    (
+                        Some(_),
+                        Some(_),
+                        Type::Reference(TypeReference {
+                            mutability: Some(_),
+                            ..
+                        }),
+                    ) if args.by_mut.is_some() => quote!((&mut **#ident)),
+                    (Some(_), Some(_), _) => quote!((&**#ident)),
+                    (None, _, _) => quote!(#ident),
+                };
+
+                match (borrow, &**ty) {
+                    // The field is boxed, so the value is moved (or cloned) out of the box.
+                    (None, ty) if recursive(ty, functions.self_ty) => quote!(*#ident),
+                    (Some(_), ty) if recursive(ty, functions.self_ty) => {
+                        quote!(::core::clone::Clone::clone(&**#ident))
+                    }
+                    (None, ty) if optional_self(ty, functions.self_ty) => {
+                        quote!(::core::option::Option::map(#ident, |value| *value))
+                    }
+                    (Some(_), ty) if optional_self(ty, functions.self_ty) => {
+                        quote!(::core::option::Option::map(
+                            ::core::clone::Clone::clone(#ident),
+                            |value| *value
+                        ))
+                    }
+
+                    // The field is stored by its owned counterpart, so it is borrowed unless the binding is already a
+                    // reference.
+                    (None, ty) if args.lifetime.is_none() && borrowed(ty).is_some() => {
+                        quote!(::core::borrow::Borrow::borrow(&#ident))
+                    }
+                    (Some(_), ty) if args.lifetime.is_none() && borrowed(ty).is_some() => {
+                        quote!(::core::borrow::Borrow::borrow(#ident))
+                    }
+
+                    (None, _) => quote!(#ident),
+
+                    // The binding is already a mutable reference to the field stored in the variant.
+                    (
+                        Some(_),
+                        Type::Reference(TypeReference {
+                            mutability: Some(_),
+                            ..
+                        }),
+                    ) if args.by_mut.is_some() => {
+                        quote!(#ident)
+                    }
+                    (Some(_), _) => quote!(::core::clone::Clone::clone(#ident)),
+                }
+            });
+
+        let context = args.context.as_ref().map(|_| quote!(__context,));
+        let parameters = parameters.names();
+
+        let mut call = Expr::Call(parse_quote!(
+            #callee::#name(#recv #context #(#inputs,)* #(#parameters),*)
+        ));
+        if !clones.is_empty() {
+            call = parse_quote!({
+                #(#clones)*
+                #call
+            });
+        }
+        // The result of a function without parameters is only computed the first time that it's called, and cloned
+        // from then on.
+        if let Some(cache) = cache(signature, functions, args) {
+            call = parse_quote!(::core::clone::Clone::clone(#cache.get_or_init(|| #call)));
+        }
+        if signature.asyncness.is_some() {
+            call = Expr::Await(parse_quote!(#call .await));
+        }
+        // The duration of the call is recorded in a histogram, labelled with the name of the variant.
+        if let Some((_, histogram)) = &args.metrics {
+            let histogram = histogram.as_ref().map_or_else(
+                || {
+                    format!(
+                        "{}_duration_seconds",
+                        extract::enum_ident(functions.self_ty)
+                            .unraw()
+                            .to_string()
+                            .to_case(Case::Snake)
+                    )
+                },
+                LitStr::value,
+            );
+            let variant = variant_ident(signature, args).to_string();
+            call = parse_quote!({
+                let start = ::std::time::Instant::now();
+                let output = #call;
+                ::metrics::histogram!(#histogram, "variant" => #variant).record(start.elapsed());
+                output
+            });
+        }
+        // The call is made inside of a span named after the variant, which is entered for as long as the call takes
+        // (or attached to the future of an `async` function).
+        if let Some((_, fields)) = &args.tracing {
+            let name = variant_ident(signature, args).to_string();
+            let values = typed_inputs(&signature.inputs)
+                .enumerate()
+                .filter(|_| *fields && args.unit_variants.is_none())
+                .map(|(index, PatType { pat, .. })| {
+                    let ident = parameter_name(&signature.inputs, index, pat);
+                    quote!(#ident = ?#ident)
+                });
+            let span = quote!(::tracing::info_span!(#name #(, #values)*));
+            call = match signature.asyncness {
+                // The span is created before the future, which takes ownership of the fields (so that they can't
+                // be recorded in the span afterwards).
+                Some(_) => parse_quote!({
+                    let span = #span;
+                    ::tracing::Instrument::instrument(async move { #call }, span).await
+                }),
+                None => parse_quote!({
+                    let _span = #span.entered();
+                    #call
+                }),
+            };
+        }
+        // A message naming the function is logged before it is called.
+        if args.log.is_some() {
+            let message = format!("dispatching {}", name.unraw());
+            call = parse_quote!({
+                ::log::debug!(#message);
+                #call
+            });
+        }
+
+        call
+    }
+}
+
+/// The return type of `map` when the `box_return` argument is provided, which replaces the `impl Trait` returned by
+/// the functions with a boxed trait object.
+pub fn box_return(functions: &Functions<'_>, args: &Args) -> Option<ReturnType> {
+    let (arg, _) = args.box_return.as_ref()?;
+    let path = args.box_return()?;
+    match &functions.return_type {
+        ReturnType::Type(arrow, ty) => match &**ty {
+            Type::ImplTrait(TypeImplTrait { bounds, .. }) => {
+                Some(parse_quote!(#arrow #path<dyn #bounds>))
+            }
+            ty => {
+                emit_error!(ty, "`{}` requires that functions return `impl Trait`", arg);
+                None
+            }
+        },
+        ReturnType::Default if !functions.signatures.is_empty() => {
+            emit_error!(arg, "`{}` requires that functions return `impl Trait`", arg);
+            None
+        }
+        ReturnType::Default => None,
+    }
+}
+
+/// Converts the value returned by a call to a function into the return type of `map`, by boxing it when the
+/// `box_return` or `return_as` arguments are provided or wrapping it in the `enum` generated by the `output_enum`
+/// argument. With `assume_safe`, calls to `unsafe` functions are placed in an `unsafe` block first.
+pub fn wrap_call(call: Expr, signature: &Signature, return_type: &ReturnType, args: &Args) -> Expr {
+    let call = match (&args.assume_safe, &signature.unsafety) {
+        (Some(_), Some(_)) => parse_quote!(unsafe { #call }),
+        _ => call,
+    };
+    let ReturnType::Type(_, ty) = return_type else {
+        return call;
+    };
+    if let Some(path) = args.box_return() {
+        return parse_quote!(#path::new(#call) as #ty);
+    }
+    if args.output_enum.is_some() {
+        let variant = variant_ident(signature, args);
+        return match signature.output {
+            ReturnType::Type(..) => parse_quote!(#ty::#variant(#call)),
+            ReturnType::Default => parse_quote!({
+                #call;
+                #ty::#variant
+            }),
+        };
+    }
+
+    call
+}
+
+/// The type that every function returns on success, which `try_map` returns alongside the error type provided.
+pub fn ok_type<'a>(functions: &Functions<'a>) -> Option<&'a Type> {
+    let mut r = None;
+    let mut valid = true;
+    for signature in &functions.signatures {
+        let ok = match &signature.output {
+            ReturnType::Type(_, ty) => result_ok(ty),
+            ReturnType::Default => None,
+        };
+        match (ok, r) {
+            (None, _) => {
+                emit_error!(
+                    signature.ident,
+                    "`try_map` requires that functions return `Result<T, E>`"
+                );
+                valid = false;
+            }
+            (Some(ok), Some(first)) if ok != first => {
+                emit_error!(
+                    ok,
+                    "`try_map` requires that functions return the same `T` in `Result<T, E>`, expected `{}`",
+                    pretty(quote!(#first))
+                );
+                valid = false;
+            }
+            (Some(ok), _) => r = r.or(Some(ok)),
+        }
+    }
+
+    r.filter(|_| valid)
+}
+
+/// The type of the value inside of `Result<T, E>`.
+fn result_ok(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let segment = path
+        .segments
+        .last()
+        .filter(|segment| segment.ident == "Result")?;
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.iter().collect::<Vec<_>>()[..] {
+        [GenericArgument::Type(ok), GenericArgument::Type(_)] => Some(ok),
+        _ => None,
+    }
+}
+
+/// The name of the `enum` generated by the `output_enum` argument.
+pub fn output_ident(enum_name: &Type, args: &Args) -> Option<Ident> {
+    args.output_enum.as_ref().map(|(_, name, _)| match name {
+        Some(name) => name.clone(),
+        None => format_ident!("{}Output", extract::enum_ident(enum_name)),
+    })
+}
+
+/// An `enum` holding the value returned by any of the functions, which is returned by `map` when they have different
+/// return types.
+pub fn output_enum(
+    enum_name: &Type,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> Option<TokenStream> {
+    let (arg, _, derives) = args.output_enum.as_ref()?;
+    if !functions.generics.params.is_empty() {
+        emit_error!(arg, "`{}` is not supported for generic `impl` blocks", arg);
+        return None;
+    }
+
+    let vis = args.nested_enum_vis();
+    let output = output_ident(enum_name, args)?;
+    let variants = functions
+        .signatures
+        .iter()
+        .zip(cfgs(functions))
+        .map(|(signature, cfg)| {
+            let variant = variant_ident(signature, args);
+            match &signature.output {
+                ReturnType::Type(_, ty) => quote!(#cfg #variant(#ty)),
+                ReturnType::Default => quote!(#cfg #variant),
+            }
+        });
+
+    Some(quote! {
+        /// The value returned by the function that corresponds to a variant of
+        #[doc = concat!("[`", stringify!(#enum_name), "`].")]
+        #[derive(#(#derives),*)]
+        #vis enum #output {
+            #(#variants,)*
+        }
+    })
+}
+
+/// The arms of a `match` on the `enum` that bind the fields of each variant and evaluate the corresponding call.
+pub fn arms<T: ToTokens>(
+    variants: &Variants,
+    functions: &Functions<'_>,
+    calls: impl IntoIterator<Item = T>,
+) -> Vec<TokenStream> {
+    variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .zip(calls)
+        .map(|(((variant, structure), signature), call)| {
+            let cfg = cfg(variant);
+            let ident = &variant.ident;
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            quote!(#cfg Self::#ident #bindings => #call,)
+        })
+        .collect()
+}
+
+/// A pattern for a variant that binds each of its fields to the name of the corresponding parameter.
+pub fn bindings(
+    variant: &Variant,
+    structure: Option<&ItemStruct>,
+    signature: &Signature,
+) -> Option<TokenStream> {
+    if let Some(ItemStruct {
+        ident,
+        fields: Fields::Named(fields),
+        ..
+    }) = structure
+    {
+        let no_types = Field::without_types(&fields.named);
+        return Some(quote! { (#ident { #no_types }) });
+    }
+
+    match &variant.fields {
+        Fields::Named(fields) => {
+            let no_types = Field::without_types(&fields.named);
+            Some(quote! { { #no_types } })
+        }
+
+        // Tuple fields don't carry the parameter names, so they are taken from the function signature instead.
+        Fields::Unnamed(_) => {
+            let no_types = FnArg::without_types(&signature.inputs);
+            Some(quote! { ( #no_types ) })
+        }
+        Fields::Unit => None,
+    }
+}
+
+/// The names of the values stored in a variant, which are the names of the parameters of the function.
+fn field_names(variant: &Variant, signature: &Signature) -> impl Iterator<Item = Ident> {
+    match &variant.fields {
+        Fields::Named(fields) => Field::without_types(&fields.named),
+        Fields::Unnamed(_) => FnArg::without_types(&signature.inputs),
+        Fields::Unit => Punctuated::new(),
+    }
+    .into_iter()
+}
+
+/// Checks whether a reference has no (named) lifetime.
+fn elided(reference: &TypeReference) -> bool {
+    reference
+        .lifetime
+        .as_ref()
+        .is_none_or(|lifetime| lifetime.ident == "_")
+}
+
+/// Checks whether a type is that of the `enum` itself (written either as `Self` or by its name). If the type of the
+/// `impl` block is given by its path, the `enum` can also be written by the last segment of that path, since that is
+/// the name that it is declared with.
+fn recursive(ty: &Type, enum_name: &Type) -> bool {
+    match (ty, enum_name) {
+        (Type::Path(TypePath { qself: None, path }), _) if path.is_ident("Self") => true,
+        (
+            Type::Path(TypePath {
+                qself: None,
+                path:
+                    Path {
+                        leading_colon: None,
+                        segments,
+                    },
+            }),
+            Type::Path(TypePath {
+                qself: None,
+                path: enum_path,
+            }),
+        ) if segments.len() == 1 => segments.last() == enum_path.segments.last(),
+        (ty, enum_name) => ty == enum_name,
+    }
+}
+
+/// Checks whether a parameter is stored in its variant as a boxed `enum`: either it is of the type of the `enum` itself,
+/// or it is a reference to the `enum` that is stored by value.
+fn boxed_self(ty: &Type, enum_name: &Type, args: &Args) -> bool {
+    match ty {
+        Type::Reference(reference) if recursive(&reference.elem, enum_name) => {
+            match reference.mutability {
+                Some(_) => args.by_mut.is_some(),
+                None => args.lifetime.is_none() && elided(reference),
+            }
+        }
+        ty => recursive(ty, enum_name),
+    }
+}
+
+/// Checks whether a type is an `Option` of the `enum` itself, which is stored as an `Option` of a boxed `enum`.
+fn optional_self(ty: &Type, enum_name: &Type) -> bool {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return false;
+    };
+    match path.segments.last() {
+        Some(PathSegment {
+            ident,
+            arguments: PathArguments::AngleBracketed(arguments),
+        }) if ident == "Option" && arguments.args.len() == 1 => {
+            matches!(arguments.args.first(), Some(GenericArgument::Type(ty)) if recursive(ty, enum_name))
+        }
+        _ => false,
+    }
+}
+
+/// Replaces `Self` in the type of a field with the type of the `enum`, since `Self` would refer to the argument
+/// `struct` of the variant instead (if there is one), and can't be used outside of the `impl` block.
+fn replace_self(ty: &mut Type, enum_name: &Type) {
+    struct ReplaceSelf<'a>(&'a Type);
+    impl VisitMut for ReplaceSelf<'_> {
+        fn visit_type_mut(&mut self, ty: &mut Type) {
+            match ty {
+                Type::Path(TypePath { qself: None, path }) if path.is_ident("Self") => {
+                    *ty = self.0.clone();
+                }
+                ty => visit_mut::visit_type_mut(self, ty),
+            }
+        }
+    }
+
+    ReplaceSelf(enum_name).visit_type_mut(ty);
+}
+
+/// The type stored in a box by a function marked `boxed`.
+fn unboxed(ty: &Type) -> &Type {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => match path.segments.last() {
+            Some(PathSegment {
+                arguments: PathArguments::AngleBracketed(arguments),
+                ..
+            }) => match arguments.args.first() {
+                Some(GenericArgument::Type(ty)) => ty,
+                _ => ty,
+            },
+            _ => ty,
+        },
+        ty => ty,
+    }
+}
+
+/// The default value of a parameter, which is boxed if the function is marked `boxed`.
+fn default_value(default: &Expr, function_args: &FunctionArgs) -> TokenStream {
+    match &function_args.boxed {
+        Some(_) => quote!(::enum_from_functions::__private::Box::new(#default)),
+        None => quote!(#default),
+    }
+}
+
+/// The type behind a shared reference without a (named) lifetime, which is stored in the variant as its owned
+/// counterpart unless the `lifetime` argument is provided.
+fn borrowed(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Reference(reference) if reference.mutability.is_none() && elided(reference) => {
+            Some(&reference.elem)
+        }
+        _ => None,
+    }
+}
+
+/// Renders tokens as they would usually be written, rather than with a space between every token.
+///
+/// This is only used to quote a single type inline in an error message. `prettyplease` isn't used here because it
+/// formats whole files, with line breaks and a trailing newline, and because the macros would then depend on it even
+/// though nothing it formats ends up in the generated code.
+pub fn pretty(tokens: TokenStream) -> String {
+    fn push(r: &mut String, previous: &mut String, token: &str, space: bool) {
+        const NO_SPACE_AFTER: [&str; 8] = ["&", "(", "[", "<", "::", "'", "*", "#"];
+        const NO_SPACE_BEFORE: [&str; 8] = [",", ")", "]", ">", "::", ":", ";", "?"];
+        if space
+            && !r.is_empty()
+            && !NO_SPACE_AFTER.contains(&previous.as_str())
+            && !NO_SPACE_BEFORE.contains(&token)
+        {
+            r.push(' ');
+        }
+        r.push_str(token);
+        *previous = token.to_owned();
+    }
+
+    fn walk(tokens: TokenStream, r: &mut String, previous: &mut String) {
+        let mut joint = false;
+        for token in tokens {
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::None => ("", ""),
+                    };
+                    // Parentheses directly follow the name of a function or type.
+                    let space = !(open == "("
+                        && previous
+                            .chars()
+                            .last()
+                            .is_some_and(|c| c.is_alphanumeric() || c == '_'));
+                    push(r, previous, open, space);
+                    walk(group.stream(), r, previous);
+                    push(r, previous, close, true);
+                    joint = false;
+                }
+                TokenTree::Punct(punct) => {
+                    let token = punct.as_char().to_string();
+                    if joint {
+                        // Punctuation that is part of a longer operator (such as `->`) is kept together.
+                        r.push_str(&token);
+                        previous.push_str(&token);
+                    } else {
+                        let space = !(token == "<"
+                            && previous
+                                .chars()
+                                .last()
+                                .is_some_and(|c| c.is_alphanumeric() || c == '_'));
+                        push(r, previous, &token, space);
+                    }
+                    joint = punct.spacing() == Spacing::Joint || token == "'";
+                }
+                token => {
+                    let token = token.to_string();
+                    if joint {
+                        r.push_str(&token);
+                        previous.push_str(&token);
+                    } else {
+                        push(r, previous, &token, true);
+                    }
+                    joint = false;
+                }
+            }
+        }
+    }
+
+    let mut r = String::new();
+    walk(tokens, &mut r, &mut String::new());
+    r
+}
+
+/// Checks whether an identifier appears anywhere in some tokens.
+pub fn mentions(tokens: TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(token) => token == *ident,
+        TokenTree::Group(group) => mentions(group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// The variants holding the `enum`s given to `nest`, which are named after them, followed by the variant holding a
+/// closure given to `custom`.
+pub fn extra_variants(
+    functions: &Functions<'_>,
+    parameters: &[PatType],
+    args: &Args,
+) -> Vec<TokenStream> {
+    let mut r = Vec::new();
+    if let Some((_, paths)) = &args.nest {
+        r.extend(paths.iter().filter_map(|path| {
+            let ident = &path.segments.last()?.ident;
+            let doc = format!(
+                "Delegates to [`{}`].",
+                quote!(#path).to_string().replace(' ', "")
+            );
+            Some(quote!(#[doc = #doc] #ident(#path)))
+        }));
+    }
+    if let Some(ident) = args.custom_ident() {
+        // The closure can only be called as many times as `map` allows.
+        let closure = match (&args.by_ref, &args.by_mut) {
+            (Some(_), _) => quote!(Fn),
+            (_, Some(_)) => quote!(FnMut),
+            _ => quote!(FnOnce),
+        };
+        let types = parameters.iter().map(|parameter| &parameter.ty);
+        let output = output_type(&functions.return_type);
+        r.push(quote! {
+            /// A closure that is called by `map` in place of a function.
+            #ident(::enum_from_functions::__private::Box<dyn #closure(#(#types),*) -> #output>)
+        });
+    }
+    r
+}
+
+/// The arms of a `match` on `self` for the variants returned by `extra_variants`, with each call converted by `wrap`.
+/// The `enum`s given to `nest` are delegated to by calling the function named `ident` on them, which has to return the
+/// same type as the function that the arms are in (which is checked where the `enum` is given), and the closure given
+/// to `custom` is called. Both are passed the parameters.
+pub fn extra_arms(
+    functions: &Functions<'_>,
+    parameters: &[PatType],
+    ident: &Ident,
+    args: &Args,
+    wrap: impl Fn(TokenStream) -> TokenStream,
+) -> TokenStream {
+    let names = parameters.iter().map(|parameter| &parameter.pat);
+    let mut arms = Vec::new();
+    if let Some((_, paths)) = &args.nest {
+        arms.extend(paths.iter().filter_map(|path| {
+            let variant = &path.segments.last()?.ident;
+            let names = names.clone();
+            let mut call = quote_spanned!(path.span()=> nested.#ident(#(#names),*));
+            if functions.asyncness.is_some() {
+                call = quote_spanned!(path.span()=> #call.await);
+            }
+            let call = wrap(call);
+            Some(quote!(Self::#variant(nested) => #call,))
+        }));
+    }
+    if let Some(variant) = args.custom_ident() {
+        let call = wrap(quote!(custom(#(#names),*)));
+        arms.push(quote!(Self::#variant(custom) => #call,));
+    }
+    quote!(#(#arms)*)
+}
+
+/// The match arm for the hidden variant holding unused generic parameters (if there is one), which can never be
+/// reached.
+pub fn phantom_arm(variants: &Variants, path: TokenStream) -> Option<TokenStream> {
+    variants.2.as_ref().map(|_| {
+        quote! {
+            #[allow(unreachable_patterns)]
+            #path::__Phantom(..) => ::core::unreachable!(),
+        }
+    })
+}
+
+/// The generic parameters of the `enum`, in the order that they are provided to it in the `impl` block.
+pub fn enum_generics(enum_name: &Type, generics: &Generics) -> Option<TokenStream> {
+    let Type::Path(TypePath { path, .. }) = enum_name else {
+        return None;
+    };
+    let PathArguments::AngleBracketed(arguments) = &path.segments.last()?.arguments else {
+        return None;
+    };
+    let params = arguments.args.iter().map(|argument| {
+        let param = generics
+            .params
+            .iter()
+            .find(|param| match (param, argument) {
+                (GenericParam::Type(param), GenericArgument::Type(Type::Path(path))) => {
+                    path.path.is_ident(&param.ident)
+                }
+                (GenericParam::Lifetime(param), GenericArgument::Lifetime(lifetime)) => {
+                    param.lifetime == *lifetime
+                }
+                // Const parameters are parsed as types, since they can't be told apart.
+                (GenericParam::Const(param), GenericArgument::Type(Type::Path(path))) => {
+                    path.path.is_ident(&param.ident)
+                }
+                (GenericParam::Const(param), GenericArgument::Const(Expr::Path(path))) => {
+                    path.path.is_ident(&param.ident)
+                }
+                _ => false,
+            });
+        match param {
+            Some(param) => quote!(#param),
+            None => {
+                emit_error!(argument, "expected a generic parameter of the `impl` block");
+                quote!()
+            }
+        }
+    });
+
+    Some(quote!(<#(#params),*>))
+}
+
+/// The `#[inline]` attribute to apply to generated functions (if any).
+pub fn inline(args: &Args) -> Option<TokenStream> {
+    args.inline.as_ref().map(|inline| quote!(#[#inline]))
+}
+
+/// The type returned by a function, which is `()` if it doesn't declare one.
+pub fn output_type(output: &ReturnType) -> TokenStream {
+    match output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    }
+}
+
+/// The name of a function, as presented by the generated helpers.
+pub fn name(signature: &Signature) -> String {
+    signature.ident.unraw().to_string()
+}
+
+/// The name of a function where it is used as a string (such as by `display`, `from_str` and `names`), converted into
+/// the case given to `rename_all`.
+pub fn string_name(signature: &Signature, args: &Args) -> String {
+    match args.rename_all {
+        Some(case) => name(signature).to_case(case),
+        None => name(signature),
+    }
+}
+
+/// The `#[cfg(...)]` attributes of a variant, which are copied from its function onto anything generated for it (such
+/// as match arms).
+pub fn cfg(variant: &Variant) -> TokenStream {
+    let cfgs = variant
+        .attrs
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("cfg"));
+    quote!(#(#cfgs)*)
+}
+
+/// The `#[cfg(...)]` attributes of each function.
+fn cfgs<'a>(functions: &'a Functions<'_>) -> impl Iterator<Item = TokenStream> + 'a {
+    functions.args.iter().map(|args| {
+        let cfgs = &args.cfgs;
+        quote!(#(#cfgs)*)
+    })
+}
+
+/// The number of functions that are enabled by their `#[cfg(...)]` attributes, as a constant expression.
+fn len(functions: &Functions<'_>) -> TokenStream {
+    if functions.args.iter().all(|args| args.cfgs.is_empty()) {
+        let len = functions.signatures.len();
+        quote!(#len)
+    } else {
+        let cfgs = cfgs(functions);
+        quote!({ [#(#cfgs ()),*].len() })
+    }
+}
+
+/// The index of each function among those that are enabled by their `#[cfg(...)]` attributes, as a constant
+/// expression.
+fn indices<'a>(functions: &'a Functions<'_>) -> impl Iterator<Item = TokenStream> + 'a {
+    let plain = functions.args.iter().all(|args| args.cfgs.is_empty());
+    (0..functions.signatures.len()).map(move |index| {
+        if plain {
+            quote!(#index)
+        } else {
+            let cfgs = cfgs(functions).take(index);
+            quote!({ <[()]>::len(&[#(#cfgs ()),*]) })
+        }
+    })
+}
+
+/// A pattern for each variant that matches it regardless of its fields, preceded by its `#[cfg(...)]` attributes.
+fn patterns(variants: &Variants) -> impl Iterator<Item = TokenStream> + '_ {
+    variants.0.iter().map(|variant| {
+        let cfg = cfg(variant);
+        let ident = &variant.ident;
+        quote!(#cfg Self::#ident { .. })
+    })
+}
+
+/// The expression to match on in generated methods taking `&self`. An empty `enum` can't be matched through a
+/// reference, so it is dereferenced instead.
+fn scrutinee(variants: &Variants) -> TokenStream {
+    if variants.0.is_empty() {
+        quote!(*self)
+    } else {
+        quote!(self)
+    }
+}
+
+/// Checks that every variant is fieldless, which is required by some of the generated helpers. An error is emitted for
+/// each function with parameters.
+pub fn fieldless(variants: &Variants, functions: &Functions<'_>, arg: &Ident) -> bool {
+    let mut r = true;
+    for (variant, signature) in variants.0.iter().zip(&functions.signatures) {
+        if !variant.fields.is_empty() {
+            emit_error!(
+                signature.inputs,
+                "`{}` requires that functions do not take any parameters",
+                arg
+            );
+            r = false;
+        }
+    }
+
+    r
+}
+
+pub fn display(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let phantom = phantom_arm(variants, quote!(Self));
+    let scrutinee = scrutinee(variants);
+    let patterns = patterns(variants);
+    let names = functions
+        .signatures
+        .iter()
+        .map(|signature| string_name(signature, args));
+
+    quote! {
+        impl #impl_generics ::core::fmt::Display for #enum_name #where_clause {
+            #inline
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match #scrutinee {
+                    #(#patterns => f.write_str(#names),)*
+                    #phantom
+                }
+            }
+        }
+    }
+}
+
+/// Removes the visibility from every function in the `impl` block.
+pub fn make_private(input: &mut ItemImpl) {
+    for item in &mut input.items {
+        if let ImplItem::Fn(function) = item {
+            function.vis = Visibility::Inherited;
+        }
+    }
+}
+
+/// Removes attributes that are only meaningful on the fields of the variants (such as `#[serde(...)]`) from the
+/// parameters of every function in the `impl` block.
+pub fn strip_parameter_attributes(input: &mut ItemImpl) {
+    for item in &mut input.items {
+        if let ImplItem::Fn(function) = item {
+            strip_signature(&mut function.sig);
+        }
+    }
+}
+
+fn strip_signature(signature: &mut Signature) {
+    const BUILTIN: [&str; 6] = ["cfg_attr", "allow", "warn", "deny", "forbid", "expect"];
+
+    for input in &mut signature.inputs {
+        if let FnArg::Typed(PatType { attrs, .. }) = input {
+            attrs.retain(|attribute| {
+                BUILTIN
+                    .iter()
+                    .any(|builtin| attribute.path().is_ident(builtin))
+            });
+        }
+    }
+}
+
+/// The input of the macro without the arguments of the macro attribute, which is emitted alongside the errors if the
+/// expansion fails.
+pub fn dummy(input: &proc_macro::TokenStream) -> TokenStream {
+    if let Ok(mut input) = syn::parse::<ItemImpl>(input.clone()) {
+        FunctionArgs::strip(&mut input);
+        strip_parameter_attributes(&mut input);
+        quote!(#input)
+    } else if let Ok(mut input) = syn::parse::<ItemMod>(input.clone()) {
+        fill_module(&mut input, TokenStream::new(), &Args::default());
+        quote!(#input)
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// A declaration of the `enum` and a `map` function that can't be called, which are emitted alongside the input if the
+/// expansion fails so that code using them keeps working (such as completions in an editor) while the errors are
+/// fixed.
+pub fn dummy_enum(
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+    attributes: &[Attribute],
+) -> TokenStream {
+    let enum_name = functions.self_ty;
+    let enum_ident = extract::enum_ident(enum_name);
+    let enum_generics = enum_generics(enum_name, functions.generics);
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let vis = args.nested_enum_vis();
+
+    // Variants with the same name would only cause more errors.
+    let mut names = Vec::new();
+    let variants_iter = variants
+        .0
+        .iter()
+        .filter(|variant| {
+            let unique = !names.contains(&variant.ident);
+            names.push(variant.ident.clone());
+            unique
+        })
+        .chain(&variants.2);
+
+    let map_ident = args.map_ident();
+    let map = (args.no_map.is_none()
+        && !functions
+            .signatures
+            .iter()
+            .any(|signature| signature.ident == map_ident))
+    .then(|| {
+        let map_vis = args.nested_map_vis();
+        let receiver = match (&args.by_ref, &args.by_mut) {
+            (Some(_), _) => quote!(&self),
+            (_, Some(_)) => quote!(&mut self),
+            _ => quote!(self),
+        };
+        let parameters = Parameters::new(functions, args).0;
+        let asyncness = functions.asyncness.filter(|_| args.boxed_future.is_none());
+        let unsafety = &functions.map_unsafety(args);
+        let return_type = &functions.return_type;
+
+        quote! {
+            impl #impl_generics #enum_name #where_clause {
+                #[allow(unused_variables)]
+                #map_vis #asyncness #unsafety fn #map_ident(#receiver #(, #parameters)*) #return_type {
+                    ::core::unimplemented!()
+                }
+            }
+        }
+    });
+
+    quote! {
+        #(#attributes)*
+        #vis enum #enum_ident #enum_generics #where_clause {
+            #(#variants_iter,)*
+        }
+
+        #map
+    }
+}
+
+/// Makes the same changes to the free functions of a module as are made to the functions of an `impl` block, and adds
+/// the generated items to it.
+pub fn fill_module(input: &mut ItemMod, generated: TokenStream, args: &Args) {
+    input.attrs.clear();
+    let Some((_, items)) = &mut input.content else {
+        return;
+    };
+    for item in items.iter_mut() {
+        if let Item::Fn(function) = item {
+            function
+                .attrs
+                .retain(|attribute| !FunctionArgs::is_attribute(attribute));
+            strip_signature(&mut function.sig);
+            if args.private_functions.is_some() {
+                function.vis = Visibility::Inherited;
+            }
+        }
+    }
+    items.push(Item::Verbatim(generated));
+}
+
+/// Adjusts a visibility so that it has the same meaning from inside of a child module.
+pub fn nest_visibility(vis: Option<&Visibility>) -> Visibility {
+    match vis {
+        None | Some(Visibility::Inherited) => parse_quote!(pub(super)),
+        Some(Visibility::Restricted(restricted)) => {
+            let path = &restricted.path;
+            match path.segments.first() {
+                Some(first) if first.ident == "self" => {
+                    let rest = path.segments.iter().skip(1);
+                    parse_quote!(pub(in super #(::#rest)*))
+                }
+                Some(first) if first.ident == "super" => parse_quote!(pub(in super::#path)),
+                _ => Visibility::Restricted(restricted.clone()),
+            }
+        }
+        Some(vis) => vis.clone(),
+    }
+}
+
+pub trait WithoutTypes: Sized {
+    fn without_types(from: &Punctuated<Self, Token![,]>) -> Punctuated<Ident, Token![,]>;
+}
+
+impl WithoutTypes for FnArg {
+    fn without_types(from: &Punctuated<Self, Token![,]>) -> Punctuated<Ident, Token![,]> {
+        typed_inputs(from)
+            .enumerate()
+            .map(|(index, pat_type)| parameter_name(from, index, &pat_type.pat))
+            .collect()
+    }
+}
+
+/// The parameters of a function other than its receiver.
+fn typed_inputs(inputs: &Punctuated<FnArg, Token![,]>) -> impl Iterator<Item = &PatType> {
+    inputs.iter().filter_map(|input| match input {
+        FnArg::Typed(pat_type) => Some(pat_type),
+        FnArg::Receiver(_) => None,
+    })
+}
+
+/// The name of the field that stores a parameter. Parameters that are destructured by a pattern (such as
+/// `(a, b): (i32, i32)`) or ignored with `_` are named after their position instead, and the whole value is passed to
+/// the function, which destructures it as usual. Underscores are appended to that name while another parameter of the
+/// function already has it.
+fn parameter_name(inputs: &Punctuated<FnArg, Token![,]>, index: usize, pat: &Pat) -> Ident {
+    match pat {
+        Pat::Ident(PatIdent { ident, .. }) => ident.clone(),
+        _ => {
+            let mut name = format_ident!("arg{}", index);
+            while typed_inputs(inputs).any(
+                |input| matches!(&*input.pat, Pat::Ident(PatIdent { ident, .. }) if *ident == name),
+            ) {
+                name = format_ident!("{}_", name);
+            }
+            name
+        }
+    }
+}
+
+impl WithoutTypes for Field {
+    fn without_types(from: &Punctuated<Self, Token![,]>) -> Punctuated<Ident, Token![,]> {
+        Punctuated::from_iter(from.pairs().map(|pair| {
+            Pair::new(
+                pair.value().ident.as_ref().unwrap().clone(),
+                pair.punct().map(|p| **p),
+            )
+        }))
+    }
+}
+
+/// The name of the error type returned when a string does not match the name of any function.
+fn parse_error_ident(enum_name: &Type) -> Ident {
+    format_ident!("Parse{}Error", extract::enum_ident(enum_name))
+}
+
+/// The error type shared by `from_str`, `dispatch_by_name` and `registry`.
+pub fn parse_error(enum_name: &Type, args: &Args) -> TokenStream {
+    let vis = args.nested_enum_vis();
+    let error = parse_error_ident(enum_name);
+
+    quote! {
+        /// The error returned when a string does not match the name of any function.
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::marker::Copy, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #vis struct #error;
+
+        impl ::core::fmt::Display for #error {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str("string does not match the name of any function")
+            }
+        }
+
+        impl ::core::error::Error for #error {}
+    }
+}
+
+pub fn from_str(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let error = parse_error_ident(enum_name);
+    let names = functions
+        .signatures
+        .iter()
+        .map(|signature| string_name(signature, args));
+    let variant_names = variants.0.iter().map(|variant| &variant.ident);
+    let cfgs = cfgs(functions);
+
+    quote! {
+        impl #impl_generics ::core::str::FromStr for #enum_name #where_clause {
+            type Err = #error;
+
+            #inline
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match s {
+                    #(#cfgs #names => ::core::result::Result::Ok(Self::#variant_names {}),)*
+                    _ => ::core::result::Result::Err(#error),
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<&str> for #enum_name #where_clause {
+            type Error = #error;
+
+            #inline
+            fn try_from(s: &str) -> ::core::result::Result<Self, Self::Error> {
+                ::core::str::FromStr::from_str(s)
+            }
+        }
+    }
+}
+
+pub fn dispatch_by_name(
+    enum_name: &Type,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    let error = parse_error_ident(enum_name);
+    let dispatch = named_dispatch(
+        enum_name,
+        functions,
+        arg,
+        args,
+        &format_ident!("dispatch_by_name"),
+        |_| quote!(::core::result::Result::Err(#error)),
+    )?;
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let vis = args.nested_map_vis();
+
+    Some(quote! {
+        impl #impl_generics #enum_name #where_clause {
+            /// Calls the function with the given name, if there is one.
+            #inline
+            #vis #dispatch
+        }
+    })
+}
+
+/// A `struct` that other crates can register handlers with through `inventory`, and a `dispatch_dynamic` function that
+/// calls them by name when none of the functions have that name.
+pub fn registry(
+    enum_name: &Type,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    // The handlers are stored as function pointers in a `static`, which can't be generic or `async`.
+    let mut valid = true;
+    if let Some(param) = functions.generics.params.first() {
+        emit_error!(
+            param,
+            "`{}` is not supported for generic `impl` blocks",
+            arg
+        );
+        valid = false;
+    }
+    if let Some(asyncness) = &functions.asyncness {
+        emit_error!(
+            asyncness,
+            "`{}` requires that functions are not `async`",
+            arg
+        );
+        valid = false;
+    }
+    if !valid {
+        return None;
+    }
+
+    let enum_ident = extract::enum_ident(enum_name);
+    let registration = format_ident!("{}Registration", enum_ident);
+    let error = parse_error_ident(enum_name);
+    let output = output_type(&functions.return_type);
+    // The types of the parameters that the handlers take are those that `dispatch_dynamic` takes.
+    let mut types = None;
+    let dispatch = named_dispatch(
+        enum_name,
+        functions,
+        arg,
+        args,
+        &format_ident!("dispatch_dynamic"),
+        |parameters| {
+            types = Some(
+                parameters
+                    .0
+                    .iter()
+                    .map(|parameter| parameter.ty.clone())
+                    .collect::<Vec<_>>(),
+            );
+            let names = parameters.names();
+            quote! {
+                match ::core::iter::Iterator::find(
+                    &mut ::core::iter::IntoIterator::into_iter(::inventory::iter::<#registration>),
+                    |registration| registration.name == name,
+                ) {
+                    ::core::option::Option::Some(registration) => {
+                        ::core::result::Result::Ok((registration.handler)(#(#names),*))
+                    }
+                    ::core::option::Option::None => ::core::result::Result::Err(#error),
+                }
+            }
+        },
+    )?;
+    let types = types.unwrap_or_default();
+    let inline = inline(args);
+    let (enum_vis, map_vis) = (args.nested_enum_vis(), args.nested_map_vis());
+    let doc = format!(
+        "A function registered with [`{}::dispatch_dynamic`] through `inventory::submit!`.",
+        enum_ident
+    );
+
+    Some(quote! {
+        #[doc = #doc]
+        #enum_vis struct #registration {
+            /// The name that the function is called by.
+            pub name: &'static str,
+            /// The function that is called.
+            pub handler: fn(#(#types),*) -> #output,
+        }
+
+        ::inventory::collect!(#registration);
+
+        impl #enum_name {
+            /// Calls the function with the given name, if there is one, or else the function registered with that
+            /// name.
+            #inline
+            #map_vis #dispatch
+        }
+    })
+}
+
+/// A function named `ident` that calls the function with the given name, along with the parameters that all of them
+/// take. Any other name is handled by the expression returned by `fallback`.
+fn named_dispatch(
+    enum_name: &Type,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+    ident: &Ident,
+    fallback: impl FnOnce(&Parameters) -> TokenStream,
+) -> Option<TokenStream> {
+    // The functions are called directly with the same arguments, so none of them can take `self`.
+    let mut valid = true;
+    for signature in &functions.signatures {
+        if let Some(FnArg::Receiver(receiver)) = signature.inputs.first() {
+            emit_error!(
+                receiver,
+                "`{}` requires that functions do not take `self`",
+                arg
+            );
+            valid = false;
+        }
+    }
+    match functions.shared_inputs() {
+        Ok(_) if valid => (),
+        Ok(_) => return None,
+        Err(errors) => {
+            // These have already been reported if the variants are fieldless.
+            if args.unit_variants.is_none() {
+                for err in errors {
+                    emit_error!(err.span(), err);
+                }
+            }
+            return None;
+        }
+    }
+
+    let (asyncness, unsafety) = (&functions.asyncness, &functions.map_unsafety(args));
+    let output = output_type(&functions.return_type);
+    let mut parameters = Parameters(Parameters::context(args).into_iter().collect());
+    if let Some(signature) = functions.signatures.first() {
+        parameters.0.extend(Parameters::of(signature).0);
+    }
+    let names = functions
+        .signatures
+        .iter()
+        .map(|signature| string_name(signature, args));
+    let callee = callee(functions);
+    let calls = functions.signatures.iter().map(|signature| {
+        let ident = &signature.ident;
+        let names = parameters.names();
+        let call = parse_quote!(#callee::#ident(#(#names),*));
+        let call = match signature.asyncness {
+            Some(_) => parse_quote!(#call.await),
+            None => call,
+        };
+        wrap_call(call, signature, &functions.return_type, args)
+    });
+    let cfgs = cfgs(functions);
+    let fallback = fallback(&parameters);
+    let error = parse_error_ident(enum_name);
+    let parameters = &parameters.0;
+
+    Some(quote! {
+        #asyncness #unsafety fn #ident(
+            name: &str #(, #parameters)*
+        ) -> ::core::result::Result<#output, #error> {
+            match name {
+                #(#cfgs #names => ::core::result::Result::Ok(#calls),)*
+                _ => #fallback,
+            }
+        }
+    })
+}
+
+pub fn iter(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let vis = args.nested_enum_vis();
+    let count = len(functions);
+    let variant_names = variants.0.iter().map(|variant| &variant.ident);
+    let cfgs = cfgs(functions);
+
+    quote! {
+        impl #impl_generics #enum_name #where_clause {
+            /// Every variant of the `enum`, in the order that the functions were declared.
+            #vis const VARIANTS: [Self; #count] = [#(#cfgs Self::#variant_names {}),*];
+
+            /// Returns an iterator over every variant of the `enum`, in the order that the functions were declared.
+            #inline
+            #vis fn iter() -> ::core::array::IntoIter<Self, #count> {
+                ::core::iter::IntoIterator::into_iter(Self::VARIANTS)
+            }
+        }
+    }
+}
+
+/// Implementations of `enum_map::Enum` and `enum_map::EnumArray` for a fieldless `enum`, which number the variants in
+/// the order that the functions were declared.
+pub fn enum_map(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    arg: &Ident,
+) -> Option<TokenStream> {
+    if let Some(param) = functions.generics.params.first() {
+        emit_error!(
+            param,
+            "`{}` is not supported for generic `impl` blocks",
+            arg
+        );
+        return None;
+    }
+
+    let count = len(functions);
+    let variant_names = variants.0.iter().map(|variant| &variant.ident);
+    let variant_names_again = variant_names.clone();
+    let indices = indices(functions).collect::<Vec<_>>();
+    let variant_cfgs = cfgs(functions);
+    let variant_cfgs_again = cfgs(functions);
+
+    Some(quote! {
+        impl ::enum_map::Enum for #enum_name {
+            const LENGTH: usize = #count;
+
+            #[inline]
+            fn from_usize(value: usize) -> Self {
+                match value {
+                    #(#variant_cfgs value if value == #indices => Self::#variant_names {},)*
+                    _ => ::core::panic!("index out of range for `Enum::from_usize`"),
+                }
+            }
+
+            #[inline]
+            fn into_usize(self) -> usize {
+                match self {
+                    #(#variant_cfgs_again Self::#variant_names_again {} => #indices,)*
+                }
+            }
+        }
+
+        impl<V> ::enum_map::EnumArray<V> for #enum_name {
+            type Array = [V; #count];
+        }
+    })
+}
+
+pub fn count(enum_name: &Type, functions: &Functions<'_>, args: &Args) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let vis = args.nested_enum_vis();
+    let count = len(functions);
+
+    quote! {
+        impl #impl_generics #enum_name #where_clause {
+            /// The number of variants in the `enum`.
+            #vis const COUNT: usize = #count;
+
+            /// Returns the number of variants in the `enum`.
+            #inline
+            #vis const fn len() -> usize {
+                Self::COUNT
+            }
+        }
+    }
+}
+
+pub fn into_static_str(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let ident = extract::enum_ident(enum_name);
+    let phantom = phantom_arm(variants, quote!(#ident));
+    let scrutinee = if variants.0.is_empty() {
+        quote!(*value)
+    } else {
+        quote!(value)
+    };
+    let variant_names = variants.0.iter().map(|variant| &variant.ident);
+    let names = functions
+        .signatures
+        .iter()
+        .map(|signature| string_name(signature, args));
+    let cfgs = cfgs(functions);
+
+    quote! {
+        impl #impl_generics ::core::convert::From<&#enum_name> for &'static str #where_clause {
+            #inline
+            fn from(value: &#enum_name) -> Self {
+                match #scrutinee {
+                    #(#cfgs #ident::#variant_names { .. } => #names,)*
+                    #phantom
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#enum_name> for &'static str #where_clause {
+            #inline
+            fn from(value: #enum_name) -> Self {
+                ::core::convert::From::from(&value)
+            }
+        }
+    }
+}
+
+pub fn arbitrary(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    // The lifetime of the input is added to the generic parameters of the `impl` block.
+    let mut generics = functions.generics.clone();
+    generics.params.insert(0, parse_quote!('a));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let inline = inline(args);
+    let constructors = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .map(|((variant, structure), signature)| {
+            let cfg = cfg(variant);
+            let ident = &variant.ident;
+            let names = field_names(variant, signature);
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            quote! {
+                #cfg
+                |u| {
+                    #(let #names = ::arbitrary::Arbitrary::arbitrary(u)?;)*
+                    ::core::result::Result::Ok(Self::#ident #bindings)
+                }
+            }
+        });
+
+    // The variants are chosen from a slice, since some of them may be disabled by their `#[cfg(...)]` attributes. An
+    // empty `enum` has no values to choose from.
+    let body = quote! {
+        let constructors: &[fn(&mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self>] = &[
+            #(#constructors,)*
+        ];
+        match constructors.len() {
+            0 => ::core::result::Result::Err(::arbitrary::Error::EmptyChoose),
+            len => constructors[u.int_in_range(0..=len - 1)?](u),
+        }
+    };
+
+    quote! {
+        impl #impl_generics ::arbitrary::Arbitrary<'a> for #enum_name #where_clause {
+            #inline
+            fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+                #body
+            }
+        }
+    }
+}
+
+pub fn match_macro(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    // The arms of a `macro_rules!` matcher can't be conditionally compiled.
+    let mut valid = true;
+    for cfg in functions.args.iter().flat_map(|args| &args.cfgs) {
+        emit_error!(
+            cfg,
+            "`{}` does not support functions with `#[cfg(...)]` attributes",
+            arg
+        );
+        valid = false;
+    }
+    if !valid {
+        return None;
+    }
+
+    let enum_ident = extract::enum_ident(enum_name);
+    let phantom = phantom_arm(variants, quote!(#enum_ident));
+    let name = match &args.match_macro {
+        Some((_, Some(name))) => name.clone(),
+        _ => format_ident!(
+            "match_{}",
+            enum_ident.unraw().to_string().to_case(Case::Snake)
+        ),
+    };
+
+    // Every variant must be handled, in the order that the functions were declared. Variants with fields are matched
+    // by a single token tree, so that any pattern for the fields can be used.
+    let (matchers, arms): (Vec<_>, Vec<_>) = variants
+        .0
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| {
+            let ident = &variant.ident;
+            let pattern = format_ident!("pattern{}", index);
+            let body = format_ident!("body{}", index);
+            match variant.fields {
+                Fields::Unit => (
+                    quote!(#ident => $#body:expr),
+                    quote!(#enum_ident::#ident => $#body),
+                ),
+                _ => (
+                    quote!(#ident $#pattern:tt => $#body:expr),
+                    quote!(#enum_ident::#ident $#pattern => $#body),
+                ),
+            }
+        })
+        .unzip();
+
+    Some(quote! {
+        /// Matches on a value of the `enum`, requiring an arm for every variant in the order that they were declared.
+        #[allow(unused_macros)]
+        macro_rules! #name {
+            ($value:expr, { #(#matchers),* $(,)? }) => {
+                match $value {
+                    #(#arms,)*
+                    #phantom
+                }
+            };
+        }
+    })
+}
+
+pub fn default(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> Option<TokenStream> {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let mut marked = functions
+        .args
+        .iter()
+        .enumerate()
+        .filter_map(|(index, function_args)| Some((index, function_args.default.as_ref()?)));
+    let (index, _) = marked.next()?;
+    for (_, default) in marked {
+        emit_error!(default, "only one function can be marked as `default`");
+    }
+
+    let inline = inline(args);
+    let variant = &variants.0[index];
+    let signature = functions.signatures[index];
+    let ident = &variant.ident;
+    // Parameters with a default value use it instead of the value from their type's `Default` implementation.
+    let values = field_names(variant, signature)
+        .zip(&functions.args[index].parameters)
+        .map(|(name, parameter)| match &parameter.default {
+            Some(default) => {
+                let default = default_value(default, &functions.args[index]);
+                quote!(let #name = #default;)
+            }
+            None => quote!(let #name = ::core::default::Default::default();),
+        });
+    let bindings = bindings(variant, variants.1[index].as_ref(), signature);
+    let cfg = cfg(variant);
+
+    Some(quote! {
+        #cfg
+        impl #impl_generics ::core::default::Default for #enum_name #where_clause {
+            #inline
+            fn default() -> Self {
+                #(#values)*
+                Self::#ident #bindings
+            }
+        }
+    })
+}
+
+/// Implementations of `PartialEq`, `Eq`, `PartialOrd` and `Ord` that compare the variants by the position of their
+/// functions in the `impl` block, regardless of their fields.
+pub fn ordering(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let phantom = phantom_arm(variants, quote!(Self));
+    let value = if variants.0.is_empty() {
+        quote!(*value)
+    } else {
+        quote!(value)
+    };
+    let patterns = patterns(variants);
+    let indices = 0..variants.0.len();
+    let index = quote! {
+        let index = |value: &Self| match #value {
+            #(#patterns => #indices,)*
+            #phantom
+        };
+    };
+
+    quote! {
+        impl #impl_generics ::core::cmp::PartialEq for #enum_name #where_clause {
+            #inline
+            fn eq(&self, other: &Self) -> bool {
+                #index
+                index(self) == index(other)
+            }
+        }
+
+        impl #impl_generics ::core::cmp::Eq for #enum_name #where_clause {}
+
+        impl #impl_generics ::core::cmp::PartialOrd for #enum_name #where_clause {
+            #inline
+            fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::option::Option::Some(::core::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        impl #impl_generics ::core::cmp::Ord for #enum_name #where_clause {
+            #inline
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                #index
+                ::core::cmp::Ord::cmp(&index(self), &index(other))
+            }
+        }
+    }
+}
+
+/// Documentation for the `enum` listing the function that each variant corresponds to.
+pub fn summary(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    attributes: &[Attribute],
+) -> TokenStream {
+    let enum_name = extract::enum_ident(enum_name);
+    let mut lines = Vec::new();
+    if attributes
+        .iter()
+        .any(|attribute| attribute.path().is_ident("doc"))
+    {
+        lines.push(quote!(#[doc = ""]));
+    }
+    lines.push(quote!(#[doc = "Each variant corresponds to one of the following functions:"]));
+    for ((variant, signature), function_args) in variants
+        .0
+        .iter()
+        .zip(&functions.signatures)
+        .zip(&functions.args)
+    {
+        let mut signature = (*signature).clone();
+        for input in &mut signature.inputs {
+            match input {
+                FnArg::Receiver(receiver) => receiver.attrs.clear(),
+                FnArg::Typed(pat_type) => pat_type.attrs.clear(),
+            }
+        }
+        let line = format!(
+            "- [`{1}`]({0}::{1}): `{2}`",
+            enum_name,
+            variant.ident,
+            pretty(quote!(#signature))
+        );
+
+        // Functions that are disabled by their `#[cfg(...)]` attributes are left out.
+        let predicates = function_args
+            .cfgs
+            .iter()
+            .filter_map(|cfg| match &cfg.meta {
+                Meta::List(list) => Some(&list.tokens),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        lines.push(if predicates.is_empty() {
+            quote!(#[doc = #line])
+        } else {
+            quote!(#[cfg_attr(all(#(#predicates),*), doc = #line)])
+        });
+    }
+
+    quote!(#(#lines)*)
+}
+
+pub fn debug(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let phantom = phantom_arm(variants, quote!(Self));
+    let scrutinee = scrutinee(variants);
+    let arms = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .map(|((variant, structure), signature)| {
+            let ident = &variant.ident;
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            let names = field_names(variant, signature).collect::<Vec<_>>();
+            let format = format!(
+                "{}({})",
+                name(signature),
+                names
+                    .iter()
+                    .map(|name| format!("{}: {{:?}}", name.unraw()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            let cfg = cfg(variant);
+            quote!(#cfg Self::#ident #bindings => ::core::write!(f, #format #(, #names)*))
+        });
+
+    quote! {
+        impl #impl_generics ::core::fmt::Debug for #enum_name #where_clause {
+            #inline
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match #scrutinee {
+                    #(#arms,)*
+                    #phantom
+                }
+            }
+        }
+    }
+}
+
+pub fn name_fn(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let phantom = phantom_arm(variants, quote!(Self));
+    let vis = args.nested_enum_vis();
+    let scrutinee = scrutinee(variants);
+    let patterns = patterns(variants);
+    let names = functions
+        .signatures
+        .iter()
+        .map(|signature| string_name(signature, args));
+
+    quote! {
+        impl #impl_generics #enum_name #where_clause {
+            /// Returns the name of the function that corresponds to the variant.
+            #inline
+            #vis const fn name(&self) -> &'static str {
+                match #scrutinee {
+                    #(#patterns => #names,)*
+                    #phantom
+                }
+            }
+        }
+    }
+}
+
+pub fn constructors(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let vis = args.nested_enum_vis();
+    let constructors = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .enumerate()
+        .map(|(index, ((variant, structure), signature))| {
+            let ident = &variant.ident;
+            let constructor = format_ident!("new_{}", name(signature));
+            let doc = format!("Creates a [`{0}`](Self::{0}) variant.", ident);
+
+            // The parameters of the constructor are the fields of the variant, other than those with a default value.
+            // Fields that box the `enum` itself (and those of functions marked `boxed`) take the value and box it. The
+            // default values might not be constant (and neither is boxing), so a constructor that uses them can't be
+            // `const`.
+            let mut parameters = Vec::new();
+            let mut values = Vec::new();
+            for (((name, ty), parameter), input) in field_names(variant, signature)
+                .zip(variants.field_types(index))
+                .zip(&functions.args[index].parameters)
+                .zip(typed_inputs(&signature.inputs))
+            {
+                match &parameter.default {
+                    Some(default) => {
+                        let default = default_value(default, &functions.args[index]);
+                        values.push(quote!(let #name = #default;));
+                    }
+                    None if boxed_self(&input.ty, enum_name, args) => {
+                        parameters.push(quote!(#name: #enum_name));
+                        values.push(quote!(let #name = ::enum_from_functions::__private::Box::new(#name);));
+                    }
+                    None if optional_self(&input.ty, enum_name) => {
+                        parameters.push(quote!(#name: ::core::option::Option<#enum_name>));
+                        values.push(quote!(
+                            let #name = ::core::option::Option::map(#name, ::enum_from_functions::__private::Box::new);
+                        ));
+                    }
+                    None if functions.args[index].boxed.is_some() => {
+                        let ty = unboxed(ty);
+                        parameters.push(quote!(#name: #ty));
+                        values.push(quote!(let #name = ::enum_from_functions::__private::Box::new(#name);));
+                    }
+                    None => parameters.push(quote!(#name: #ty)),
+                }
+            }
+            let constness = values.is_empty().then(|| quote!(const));
+            let bindings = bindings(variant, structure.as_ref(), signature);
+
+            let cfg = cfg(variant);
+
+            quote! {
+                #cfg
+                #[doc = #doc]
+                #inline
+                #vis #constness fn #constructor(#(#parameters),*) -> Self {
+                    #(#values)*
+                    Self::#ident #bindings
+                }
+            }
+        });
+
+    quote! {
+        impl #impl_generics #enum_name #where_clause {
+            #(#constructors)*
+        }
+    }
+}
+
+pub fn names(enum_name: &Type, functions: &Functions<'_>, args: &Args) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let vis = args.nested_enum_vis();
+    let count = len(functions);
+    let names = functions
+        .signatures
+        .iter()
+        .map(|signature| string_name(signature, args));
+    let cfgs = cfgs(functions);
+
+    quote! {
+        impl #impl_generics #enum_name #where_clause {
+            /// The names of the functions in the `impl` block, in the order that they were declared.
+            #vis const NAMES: [&'static str; #count] = [#(#cfgs #names),*];
+        }
+    }
+}
+
+/// The name of the companion fieldless `enum` generated by the `kind` argument.
+pub fn kind_ident(enum_name: &Type, args: &Args) -> Ident {
+    match &args.kind {
+        Some((_, Some(name))) => name.clone(),
+        _ => format_ident!("{}Kind", extract::enum_ident(enum_name)),
+    }
+}
+
+pub fn kind(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let phantom = phantom_arm(variants, quote!(Self));
+    let vis = args.nested_enum_vis();
+    let kind = kind_ident(enum_name, args);
+    let scrutinee = scrutinee(variants);
+    let patterns = patterns(variants).collect::<Vec<_>>();
+    let variant_names = variants
+        .0
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+    let names = functions
+        .signatures
+        .iter()
+        .map(|signature| string_name(signature, args))
+        .collect::<Vec<_>>();
+    let cfgs = cfgs(functions).collect::<Vec<_>>();
+
+    quote! {
+        /// A fieldless counterpart to
+        #[doc = concat!("[`", stringify!(#enum_name), "`],")]
+        /// with one variant for each function.
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::cmp::PartialOrd,
+            ::core::cmp::Ord,
+            ::core::hash::Hash,
+        )]
+        #vis enum #kind {
+            #(#cfgs #variant_names,)*
+        }
+
+        impl #impl_generics #enum_name #where_clause {
+            /// Returns the fieldless counterpart of the variant.
+            #inline
+            #vis const fn kind(&self) -> #kind {
+                match #scrutinee {
+                    #(#patterns => #kind::#variant_names,)*
+                    #phantom
+                }
+            }
+        }
+
+        impl #kind {
+            /// Returns the variant that corresponds to the function with the given name, if there is one.
+            #inline
+            #vis fn from_name(name: &str) -> ::core::option::Option<Self> {
+                match name {
+                    #(#cfgs #names => ::core::option::Option::Some(Self::#variant_names),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            /// Returns the name of the function that corresponds to the variant.
+            #inline
+            #vis const fn name(&self) -> &'static str {
+                match *self {
+                    #(#cfgs Self::#variant_names => #names,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<&#enum_name> for #kind #where_clause {
+            #inline
+            fn from(value: &#enum_name) -> Self {
+                value.kind()
+            }
+        }
+    }
+}
+
+/// A trait with a method for each variant, which is passed the fields of the variant by `accept`.
+pub fn visitor(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = functions.generics.split_for_impl();
+    let enum_ident = extract::enum_ident(enum_name);
+    let visitor = match &args.visitor {
+        Some((_, Some(name))) => name.clone(),
+        _ => format_ident!("{}Visitor", enum_ident.unraw()),
+    };
+    let vis = args.nested_enum_vis();
+    let inline = inline(args);
+    let phantom = phantom_arm(variants, quote!(Self));
+
+    let (methods, arms): (Vec<_>, Vec<_>) = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .enumerate()
+        .map(|(index, ((variant, structure), signature))| {
+            let variant_name = &variant.ident;
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            // The parameters aren't stored in the variant when it is fieldless.
+            let names = FnArg::without_types(&signature.inputs);
+            let names = names
+                .iter()
+                .filter(|_| !variant.fields.is_empty())
+                .collect::<Vec<_>>();
+            let types = variants.field_types(index);
+            let visit = format_ident!("visit_{}", name(signature));
+            let cfg = cfg(variant);
+            let doc = format!("Visits [`{}::{}`].", enum_ident, variant_name);
+
+            (
+                quote! {
+                    #cfg
+                    #[doc = #doc]
+                    fn #visit(&mut self #(, #names: #types)*);
+                },
+                quote!(#cfg Self::#variant_name #bindings => visitor.#visit(#(#names),*),),
+            )
+        })
+        .unzip();
+    let doc = format!(
+        "A visitor over the variants of [`{}`], whose methods are called by [`{0}::accept`] with the fields of each \
+         variant.",
+        enum_ident
+    );
+
+    quote! {
+        #[doc = #doc]
+        #vis trait #visitor #impl_generics #where_clause {
+            #(#methods)*
+        }
+
+        impl #impl_generics #enum_name #where_clause {
+            /// Calls the method of the visitor that corresponds to the variant, passing it the fields of the variant.
+            #inline
+            #vis fn accept(self, visitor: &mut impl #visitor #ty_generics) {
+                match self {
+                    #(#arms)*
+                    #phantom
+                }
+            }
+        }
+    }
+}
+
+pub fn accessors(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let vis = args.nested_enum_vis();
+    let accessors = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .enumerate()
+        .filter(|(_, ((variant, _), _))| !variant.fields.is_empty())
+        .map(|(index, ((variant, structure), signature))| {
+            let variant_name = &variant.ident;
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            let names = FnArg::without_types(&signature.inputs);
+            let names = names.iter().collect::<Vec<_>>();
+            let types = variants.field_types(index).collect::<Vec<_>>();
+            let as_name = format_ident!("as_{}", name(signature));
+            let into_name = format_ident!("into_{}", name(signature));
+
+            let cfg = cfg(variant);
+
+            quote! {
+                #cfg
+                #[doc = concat!("Returns references to the fields of the variant if it is [`Self::", stringify!(#variant_name), "`].")]
+                #inline
+                #vis fn #as_name(&self) -> ::core::option::Option<(#(&#types,)*)> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        Self::#variant_name #bindings => ::core::option::Option::Some((#(#names,)*)),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+
+                #cfg
+                #[doc = concat!("Returns the fields of the variant if it is [`Self::", stringify!(#variant_name), "`].")]
+                #inline
+                #vis fn #into_name(self) -> ::core::option::Option<(#(#types,)*)> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        Self::#variant_name #bindings => ::core::option::Option::Some((#(#names,)*)),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+        });
+
+    quote! {
+        impl #impl_generics #enum_name #where_clause {
+            #(#accessors)*
+        }
+    }
+}
+
+pub fn repr_conversions(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    repr: &Ident,
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let vis = args.nested_enum_vis();
+    let error = format_ident!("TryFrom{}Error", extract::enum_ident(enum_name));
+    let variant_names = variants
+        .0
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+    let cfgs = cfgs(functions);
+
+    quote! {
+        /// The error returned when an integer does not match the discriminant of any variant.
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::marker::Copy, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #vis struct #error(pub #repr);
+
+        impl ::core::fmt::Display for #error {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::write!(f, "`{}` does not match the discriminant of any variant", self.0)
+            }
+        }
+
+        impl ::core::error::Error for #error {}
+
+        impl #impl_generics ::core::convert::From<#enum_name> for #repr #where_clause {
+            #inline
+            fn from(value: #enum_name) -> Self {
+                value as #repr
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<#repr> for #enum_name #where_clause {
+            type Error = #error;
+
+            #inline
+            fn try_from(value: #repr) -> ::core::result::Result<Self, Self::Error> {
+                match value {
+                    #(#cfgs value if value == (Self::#variant_names {} as #repr) => {
+                        ::core::result::Result::Ok(Self::#variant_names {})
+                    })*
+                    _ => ::core::result::Result::Err(#error(value)),
+                }
+            }
+        }
+    }
+}
+
+/// The `static` in which `memoize` stores the result of a function without parameters, if it has none. It is shared by
+/// all of the generated functions that call it (such as `map` and `map_ref`), so that it is only called once.
+fn cache(signature: &Signature, functions: &Functions<'_>, args: &Args) -> Option<Ident> {
+    (args.memoize.is_some() && signature.inputs.is_empty() && args.context.is_none()).then(|| {
+        format_ident!(
+            "__{}_{}",
+            extract::enum_ident(functions.self_ty)
+                .unraw()
+                .to_string()
+                .to_case(Case::UpperSnake),
+            signature
+                .ident
+                .unraw()
+                .to_string()
+                .to_case(Case::UpperSnake)
+        )
+    })
+}
+
+/// The `static`s in which `memoize` stores the results of the functions without parameters.
+pub fn caches(functions: &Functions<'_>, args: &Args) -> TokenStream {
+    let caches =
+        functions
+            .signatures
+            .iter()
+            .zip(cfgs(functions))
+            .filter_map(|(signature, cfgs)| {
+                let cache = cache(signature, functions, args)?;
+                let output = output_type(&signature.output);
+                Some(quote! {
+                    #cfgs
+                    static #cache: ::std::sync::OnceLock<#output> = ::std::sync::OnceLock::new();
+                })
+            });
+    quote!(#(#caches)*)
+}
+
+/// Checks that the results of the functions without parameters can be stored in a `static` by `memoize`, which can't
+/// be done for `async` functions, from a `const fn` or with the generic parameters of the `impl` block.
+pub fn check_memoize(functions: &Functions<'_>, arg: &Ident) {
+    if let Some(constness) = &functions.constness {
+        emit_error!(
+            constness,
+            "`{}` requires that functions are not `const`",
+            arg
+        );
+    }
+    if let Some(param) = functions.generics.params.first() {
+        emit_error!(
+            param,
+            "`{}` is not supported for generic `impl` blocks",
+            arg
+        );
+    }
+    for signature in &functions.signatures {
+        if let (Some(asyncness), true) = (&signature.asyncness, signature.inputs.is_empty()) {
+            emit_error!(
+                asyncness,
+                "`{}` requires that functions without parameters are not `async`",
+                arg
+            );
+        }
+    }
+}
+
+/// The type of a pointer to any of the functions, which can only be produced if every function has the same signature
+/// (other than `const` and `unsafe`). This excludes receivers and `async` functions, and includes the ABI of the
+/// functions.
+fn fn_pointer(variants: &Variants, functions: &Functions<'_>, arg: &Ident) -> Option<TokenStream> {
+    let mut valid = fieldless(variants, functions, arg);
+    let abi = functions
+        .signatures
+        .first()
+        .and_then(|signature| signature.abi.as_ref());
+    let expected = match abi {
+        Some(abi) => format!("`{}`", pretty(quote!(#abi))),
+        None => String::from("the default ABI"),
+    };
+    for signature in &functions.signatures {
+        if signature.abi.as_ref() != abi {
+            let message = format!(
+                "`{}` requires that functions have the same ABI, expected {}",
+                arg, expected
+            );
+            match &signature.abi {
+                Some(other) => emit_error!(other, message),
+                None => emit_error!(signature.ident, message),
+            }
+            valid = false;
+        }
+        if let Some(FnArg::Receiver(receiver)) = signature.inputs.first() {
+            emit_error!(
+                receiver,
+                "`{}` requires that functions do not take `self`",
+                arg
+            );
+            valid = false;
+        }
+        if let Some(asyncness) = &signature.asyncness {
+            emit_error!(
+                asyncness,
+                "`{}` requires that functions are not `async`",
+                arg
+            );
+            valid = false;
+        }
+    }
+    let types = match functions.shared_inputs() {
+        Ok(inputs) if valid => inputs.into_iter().map(|input| &input.ty),
+        _ => return None,
+    };
+
+    let unsafety = &functions.unsafety;
+    let return_type = &functions.return_type;
+    Some(quote!(#unsafety #abi fn(#(#types),*) #return_type))
+}
+
+pub fn as_fn(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let pointer = fn_pointer(variants, functions, arg)?;
+
+    let inline = inline(args);
+    let phantom = phantom_arm(variants, quote!(Self));
+    let vis = args.nested_enum_vis();
+    let scrutinee = scrutinee(variants);
+    let patterns = patterns(variants);
+    let callee = callee(functions);
+    let names = functions
+        .signatures
+        .iter()
+        .map(|signature| &signature.ident);
+
+    Some(quote! {
+        impl #impl_generics #enum_name #where_clause {
+            /// Returns a pointer to the function that corresponds to the variant.
+            #inline
+            #vis const fn as_fn(&self) -> #pointer {
+                match #scrutinee {
+                    #(#patterns => #callee::#names,)*
+                    #phantom
+                }
+            }
+        }
+    })
+}
+
+/// A `JUMP_TABLE` constant holding a pointer to every function, in the order of the variants, along with the body of
+/// `map` that calls the function for the variant through it rather than matching on the variant to call it directly.
+pub fn jump_table(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<(TokenStream, TokenStream)> {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let pointer = fn_pointer(variants, functions, arg);
+
+    // The pointers are called with the return values of the functions, and `const fn`s can't call function pointers.
+    let mut valid = true;
+    if let Some(constness) = &functions.constness {
+        emit_error!(
+            constness,
+            "`{}` requires that functions are not `const`",
+            arg
+        );
+        valid = false;
+    }
+    if args.box_return().is_some() || args.output_enum.is_some() || args.context.is_some() {
+        emit_error!(
+            arg,
+            "`{}` cannot be combined with arguments that change the parameters or return type of `map`",
+            arg
+        );
+        valid = false;
+    }
+    // The functions are called through the table directly, so their calls can't be wrapped.
+    for other in [
+        args.tracing.as_ref().map(|(tracing, _)| tracing),
+        args.log.as_ref(),
+        args.metrics.as_ref().map(|(metrics, _)| metrics),
+        args.memoize.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let message = format!("cannot combine `{}` and `{}`", arg, other);
+        emit_error!(arg, message);
+        emit_error!(other, message);
+        valid = false;
+    }
+    // The variants are numbered by their position, which would be different for each configuration.
+    for (variant, signature) in variants.0.iter().zip(&functions.signatures) {
+        if !cfg(variant).is_empty() {
+            emit_error!(
+                signature.ident,
+                "`{}` is not supported for functions with `#[cfg(...)]` attributes",
+                arg
+            );
+            valid = false;
+        }
+    }
+    let pointer = pointer.filter(|_| valid)?;
+
+    let vis = args.nested_enum_vis();
+    let len = functions.signatures.len();
+    let callee = callee(functions);
+    let names = functions
+        .signatures
+        .iter()
+        .map(|signature| &signature.ident);
+    let patterns = patterns(variants);
+    let indices = 0..len;
+    let phantom = phantom_arm(variants, quote!(Self));
+    let scrutinee = match args.borrow() {
+        Some(_) => scrutinee(variants),
+        None => quote!(self),
+    };
+    let parameters = Parameters::new(functions, args);
+    let parameters = parameters.names();
+
+    let table = quote! {
+        impl #impl_generics #enum_name #where_clause {
+            /// Pointers to the functions, in the order of the variants that they correspond to.
+            #vis const JUMP_TABLE: [#pointer; #len] = [#(#callee::#names),*];
+        }
+    };
+    let mut call = quote!((Self::JUMP_TABLE[index])(#(#parameters),*));
+    if let (Some(_), Some(_)) = (&args.assume_safe, &functions.unsafety) {
+        call = quote!(unsafe { #call });
+    }
+    // The call would be unreachable for an empty `enum`, so it is only matched on.
+    let body = if len == 0 {
+        quote! {
+            match #scrutinee {
+                #phantom
+            }
+        }
+    } else {
+        quote! {
+            // The position of the variant is the same as its discriminant (unless one is given to it), so this is
+            // compiled to a conversion rather than a branch.
+            let index: usize = match #scrutinee {
+                #(#patterns => #indices,)*
+                #phantom
+            };
+            #call
+        }
+    };
+
+    Some((table, body))
+}
+
+pub fn map_all(
+    enum_name: &Type,
+    calls: &[Expr],
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    // The functions are called directly, so they can't take any parameters (including `self`).
+    let mut valid = true;
+    for signature in &functions.signatures {
+        if !signature.inputs.is_empty() {
+            emit_error!(
+                signature.inputs,
+                "`{}` requires that functions do not take any parameters",
+                arg
+            );
+            valid = false;
+        }
+    }
+    if !valid {
+        return None;
+    }
+
+    let inline = inline(args);
+    let vis = args.nested_enum_vis();
+    let (asyncness, constness, unsafety) = (
+        &functions.asyncness,
+        &functions.map_constness(args),
+        &functions.map_unsafety(args),
+    );
+    let output = output_type(&functions.return_type);
+    let count = len(functions);
+    let cfgs = cfgs(functions);
+
+    Some(quote! {
+        impl #impl_generics #enum_name #where_clause {
+            /// Calls every function in the order that they were declared, and returns their results.
+            #inline
+            #vis #asyncness #constness #unsafety fn map_all() -> [#output; #count] {
+                [#(#cfgs #calls),*]
+            }
+        }
+    })
+}
+
+/// The name of the module of tests generated by `generate_tests`, which is also where the marker recording the function
+/// that was called is defined.
+pub fn tests_ident(enum_name: &Type) -> Ident {
+    format_ident!(
+        "{}_tests",
+        extract::enum_ident(enum_name)
+            .unraw()
+            .to_string()
+            .to_case(Case::Snake)
+    )
+}
+
+/// Has a function record its name in the marker at `marker` when it is called in a test, unless a function has already
+/// been recorded (such as one that calls another function of the `enum`).
+pub fn mark(block: &mut Block, signature: &Signature, marker: &TokenStream) {
+    let name = name(signature);
+    block.stmts.insert(
+        0,
+        parse_quote! {
+            #[cfg(test)]
+            #marker.with(|called| {
+                if called.get().is_none() {
+                    called.set(::core::option::Option::Some(#name));
+                }
+            });
+        },
+    );
+}
+
+/// A test for every function in `local` (those that can be marked), which constructs its variant from the default
+/// values of its fields and checks that `map` calls it.
+pub fn tests(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    local: &[Ident],
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    let mut valid = true;
+    if let Some(param) = functions.generics.params.first() {
+        emit_error!(
+            param,
+            "`{}` is not supported for generic `impl` blocks",
+            arg
+        );
+        valid = false;
+    }
+    if let Some(unsafety) = &functions.map_unsafety(args) {
+        emit_error!(
+            unsafety,
+            "`{}` requires that functions are not `unsafe`",
+            arg
+        );
+        valid = false;
+    }
+    for signature in &functions.signatures {
+        if let Some(asyncness) = &signature.asyncness {
+            emit_error!(
+                asyncness,
+                "`{}` requires that functions are not `async`",
+                arg
+            );
+            valid = false;
+        }
+        if let Some(constness) = &signature.constness {
+            emit_error!(
+                constness,
+                "`{}` requires that functions are not `const`",
+                arg
+            );
+            valid = false;
+        }
+    }
+    if let Some(force_async) = &args.force_async {
+        emit_error!(force_async, "`{}` requires that `map` is not `async`", arg);
+        valid = false;
+    }
+    if !valid {
+        return None;
+    }
+
+    let tests = tests_ident(enum_name);
+    let map_ident = args.map_ident();
+    // The parameters that are passed to `map` rather than stored in the variants use their default values as well.
+    let parameters = Parameters::new(functions, args)
+        .0
+        .into_iter()
+        .map(|_| quote!(::core::default::Default::default()));
+    let parameters = quote!(#(#parameters),*);
+    let cases = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .enumerate()
+        .filter(|(_, (_, signature))| local.contains(&signature.ident))
+        .map(|(index, ((variant, structure), signature))| {
+            let name = name(signature);
+            let test = format_ident!("{}", name);
+            let ident = &variant.ident;
+            let values = field_names(variant, signature)
+                .zip(&functions.args[index].parameters)
+                .map(|(name, parameter)| match &parameter.default {
+                    Some(default) => {
+                        let default = default_value(default, &functions.args[index]);
+                        quote!(let #name = #default;)
+                    }
+                    None => quote!(let #name = ::core::default::Default::default();),
+                });
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            let cfg = cfg(variant);
+
+            quote! {
+                #cfg
+                #[test]
+                fn #test() {
+                    CALLED.with(|called| called.set(::core::option::Option::None));
+                    #(#values)*
+                    #[allow(unused_mut)]
+                    let mut value = #enum_name::#ident #bindings;
+                    let _ = value.#map_ident(#parameters);
+                    ::core::assert_eq!(
+                        CALLED.with(::core::cell::Cell::get),
+                        ::core::option::Option::Some(#name)
+                    );
+                }
+            }
+        });
+
+    Some(quote! {
+        #[cfg(test)]
+        pub(crate) mod #tests {
+            use super::*;
+
+            ::std::thread_local! {
+                pub(crate) static CALLED: ::core::cell::Cell<::core::option::Option<&'static str>> =
+                    const { ::core::cell::Cell::new(::core::option::Option::None) };
+            }
+
+            #(#cases)*
+        }
+    })
+}
+
+/// Checks that `map` can be called from another language, which can't uphold the safety requirements of an `unsafe`
+/// function, choose generic parameters or pass parameters to `map` itself.
+fn check_foreign(functions: &Functions<'_>, arg: &Ident, args: &Args) -> bool {
+    let mut valid = true;
+    if let Some(param) = functions.generics.params.first() {
+        emit_error!(
+            param,
+            "`{}` is not supported for generic `impl` blocks",
+            arg
+        );
+        valid = false;
+    }
+    if let Some(unsafety) = &functions.map_unsafety(args) {
+        emit_error!(
+            unsafety,
+            "`{}` requires that functions are not `unsafe`",
+            arg
+        );
+        valid = false;
+    }
+    if let Some(parameter) = Parameters::new(functions, args).0.first() {
+        emit_error!(
+            parameter,
+            "`{}` requires that `map` doesn't take any parameters",
+            arg
+        );
+        valid = false;
+    }
+    valid
+}
+
+/// The name of the error type returned by `dispatch_json`.
+fn dispatch_error_ident(enum_name: &Type) -> Ident {
+    format_ident!("Dispatch{}Error", extract::enum_ident(enum_name))
+}
+
+/// A function that constructs the variant for a function from its name and a JSON object of its parameters, and calls
+/// `map` on it, along with the error type that it returns.
+pub fn dispatch_json(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    if !check_foreign(functions, arg, args) {
+        return None;
+    }
+
+    let enum_ident = extract::enum_ident(enum_name);
+    let error = dispatch_error_ident(enum_name);
+    let (enum_vis, map_vis) = (args.nested_enum_vis(), args.nested_map_vis());
+    let map_ident = args.map_ident();
+    let asyncness = &functions.asyncness;
+    let call = match asyncness {
+        Some(_) => quote!(value.#map_ident().await),
+        None => quote!(value.#map_ident()),
+    };
+    let output = output_type(&functions.return_type);
+    let arms = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .enumerate()
+        .map(|(index, ((variant, structure), signature))| {
+            let ident = &variant.ident;
+            let name = string_name(signature, args);
+            let names = field_names(variant, signature).collect::<Vec<_>>();
+            let keys = names.iter().map(|name| name.unraw().to_string());
+            let types = variants.field_types(index);
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            let cfg = cfg(variant);
+            // Fieldless variants ignore the parameters, and missing parameters are deserialized from `null`.
+            let values = (!names.is_empty()).then(|| {
+                quote! {
+                    let mut parameters: ::serde_json::Map<::enum_from_functions::__private::String, ::serde_json::Value> =
+                        ::serde_json::from_value(parameters).map_err(#error::Parameters)?;
+                    #(
+                        let #names: #types = ::serde_json::from_value(
+                            parameters.remove(#keys).unwrap_or(::serde_json::Value::Null),
+                        )
+                        .map_err(#error::Parameters)?;
+                    )*
+                }
+            });
+            quote! {
+                #cfg
+                #name => {
+                    #values
+                    #enum_name::#ident #bindings
+                }
+            }
+        });
+    let doc = format!("The error returned by [`{}::dispatch_json`].", enum_ident);
+
+    Some(quote! {
+        #[doc = #doc]
+        #[derive(::core::fmt::Debug)]
+        #enum_vis enum #error {
+            /// The name does not match the name of any function.
+            UnknownFunction,
+            /// The parameters could not be deserialized.
+            Parameters(::serde_json::Error),
+        }
+
+        impl ::core::fmt::Display for #error {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    Self::UnknownFunction => f.write_str("string does not match the name of any function"),
+                    Self::Parameters(err) => ::core::write!(f, "invalid parameters: {}", err),
+                }
+            }
+        }
+
+        impl ::core::error::Error for #error {
+            fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+                match self {
+                    Self::UnknownFunction => ::core::option::Option::None,
+                    Self::Parameters(err) => ::core::option::Option::Some(err),
+                }
+            }
+        }
+
+        impl #enum_name {
+            /// Calls the function with the given name, deserializing its parameters from a JSON object of them.
+            #map_vis #asyncness fn dispatch_json(
+                name: &str,
+                parameters: ::serde_json::Value,
+            ) -> ::core::result::Result<#output, #error> {
+                #[allow(unused_mut)]
+                let mut value = match name {
+                    #(#arms)*
+                    _ => return ::core::result::Result::Err(#error::UnknownFunction),
+                };
+                ::core::result::Result::Ok(#call)
+            }
+        }
+    })
+}
+
+/// A fieldless `enum` exported to JavaScript with `wasm_bindgen`, and an exported function that constructs the variant
+/// for one of its values from a JSON array of the parameters and calls `map` on it, returning the result as JSON.
+pub fn wasm(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    let mut valid = check_foreign(functions, arg, args);
+    // The exported function is synchronous, so it can't wait for a future.
+    if let Some(asyncness) = &functions.asyncness {
+        emit_error!(
+            asyncness,
+            "`{}` requires that functions are not `async`",
+            arg
+        );
+        valid = false;
+    }
+    // `wasm_bindgen` doesn't support variants that are conditionally compiled.
+    for cfg in functions.args.iter().flat_map(|args| &args.cfgs) {
+        emit_error!(
+            cfg,
+            "`{}` does not support functions with `#[cfg(...)]` attributes",
+            arg
+        );
+        valid = false;
+    }
+    if !valid {
+        return None;
+    }
+
+    let enum_ident = extract::enum_ident(enum_name);
+    let tag = format_ident!("{}Tag", enum_ident);
+    let dispatch = format_ident!(
+        "dispatch_{}",
+        enum_ident.unraw().to_string().to_case(Case::Snake)
+    );
+    let map_ident = args.map_ident();
+    let names = variants.0.iter().map(|variant| &variant.ident);
+    let arms = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .enumerate()
+        .map(|(index, ((variant, structure), signature))| {
+            let ident = &variant.ident;
+            let names = field_names(variant, signature).collect::<Vec<_>>();
+            let types = variants.field_types(index);
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            // Fieldless variants ignore the parameters.
+            let values = (!names.is_empty()).then(|| {
+                quote! {
+                    let (#(#names,)*): (#(#types,)*) = ::serde_json::from_str(parameters)?;
+                }
+            });
+            quote! {
+                #tag::#ident => {
+                    #values
+                    #enum_name::#ident #bindings
+                }
+            }
+        });
+    let doc = format!(
+        "Calls the function for a variant of [`{0}`], given its parameters as a JSON array.",
+        enum_ident
+    );
+
+    Some(quote! {
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        #[derive(::core::clone::Clone, ::core::marker::Copy)]
+        pub enum #tag {
+            #(#names,)*
+        }
+
+        #[doc = #doc]
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        pub fn #dispatch(
+            tag: #tag,
+            parameters: &str,
+        ) -> ::core::result::Result<::enum_from_functions::__private::String, ::wasm_bindgen::JsError> {
+            #[allow(unused_mut)]
+            let mut value = match tag {
+                #(#arms)*
+            };
+            ::core::result::Result::Ok(::serde_json::to_string(&value.#map_ident())?)
+        }
+    })
+}
+
+/// A function exported to Python with `pyo3`, which constructs the variant for the function with the given name from
+/// the keyword arguments named after its fields and calls `map` on it.
+pub fn pyo3(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    let mut valid = check_foreign(functions, arg, args);
+    if let Some(asyncness) = &functions.asyncness {
+        emit_error!(
+            asyncness,
+            "`{}` requires that functions are not `async`",
+            arg
+        );
+        valid = false;
+    }
+    if !valid {
+        return None;
+    }
+
+    let enum_ident = extract::enum_ident(enum_name);
+    let dispatch = format_ident!(
+        "dispatch_{}",
+        enum_ident.unraw().to_string().to_case(Case::Snake)
+    );
+    let map_ident = args.map_ident();
+    let return_type = output_type(&functions.return_type);
+    let arms = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .map(|((variant, structure), signature)| {
+            let name = string_name(signature, args);
+            let ident = &variant.ident;
+            let values = field_names(variant, signature).map(|field| {
+                let key = field.unraw().to_string();
+                quote!(let #field = ::pyo3::types::PyAnyMethods::extract(&argument(#key)?)?;)
+            });
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            let cfg = cfg(variant);
+            quote! {
+                #cfg
+                #name => {
+                    #(#values)*
+                    #enum_name::#ident #bindings
+                }
+            }
+        });
+    let doc = format!(
+        "Calls the function for a variant of [`{0}`] by its name, given its parameters as keyword arguments.",
+        enum_ident
+    );
+
+    Some(quote! {
+        #[doc = #doc]
+        #[::pyo3::pyfunction]
+        #[pyo3(signature = (name, **kwargs))]
+        pub fn #dispatch(
+            name: &str,
+            kwargs: ::core::option::Option<&::pyo3::Bound<'_, ::pyo3::types::PyDict>>,
+        ) -> ::pyo3::PyResult<#return_type> {
+            #[allow(unused)]
+            let argument = |key: &str| {
+                kwargs
+                    .map(|kwargs| ::pyo3::types::PyDictMethods::get_item(kwargs, key))
+                    .transpose()?
+                    .flatten()
+                    .ok_or_else(|| {
+                        ::pyo3::exceptions::PyTypeError::new_err(::enum_from_functions::__private::format!(
+                            "missing keyword argument `{}`",
+                            key
+                        ))
+                    })
+            };
+            #[allow(unused_mut)]
+            let mut value = match name {
+                #(#arms)*
+                _ => {
+                    return ::core::result::Result::Err(::pyo3::exceptions::PyValueError::new_err(
+                        ::enum_from_functions::__private::format!("unknown function `{}`", name),
+                    ))
+                }
+            };
+            ::core::result::Result::Ok(value.#map_ident())
+        }
+    })
+}
+
+/// A class exported to JavaScript with `napi`, which wraps the `enum` and has a factory method constructing each variant
+/// from its parameters (other than those with a default value) and a `map` method returning a promise if the functions
+/// are `async`.
+pub fn napi(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    arg: &Ident,
+    args: &Args,
+) -> Option<TokenStream> {
+    if !check_foreign(functions, arg, args) {
+        return None;
+    }
+
+    let enum_ident = extract::enum_ident(enum_name);
+    let class = format_ident!("Js{}", enum_ident);
+    let js_name = enum_ident.unraw().to_string();
+    let map_ident = args.map_ident();
+    let return_type = &functions.return_type;
+    let factories = variants
+        .0
+        .iter()
+        .zip(&variants.1)
+        .zip(&functions.signatures)
+        .enumerate()
+        .map(|(index, ((variant, structure), signature))| {
+            let ident = &variant.ident;
+            let factory = format_ident!("{}", name(signature));
+            let mut parameters = Vec::new();
+            let mut values = Vec::new();
+            for ((name, ty), parameter) in field_names(variant, signature)
+                .zip(variants.field_types(index))
+                .zip(&functions.args[index].parameters)
+            {
+                match &parameter.default {
+                    Some(default) => values.push(quote!(let #name = #default;)),
+                    None => parameters.push(quote!(#name: #ty)),
+                }
+            }
+            let bindings = bindings(variant, structure.as_ref(), signature);
+            let cfg = cfg(variant);
+            quote! {
+                #cfg
+                #[napi(factory)]
+                pub fn #factory(#(#parameters),*) -> Self {
+                    #(#values)*
+                    Self(#enum_name::#ident #bindings)
+                }
+            }
+        });
+    // The class is only borrowed by its methods, so `map` is called on a clone of the variant.
+    let map = if functions.asyncness.is_some() {
+        quote! {
+            #[napi]
+            pub async fn #map_ident(&self) #return_type {
+                ::core::clone::Clone::clone(&self.0).#map_ident().await
+            }
+        }
+    } else {
+        quote! {
+            #[napi]
+            pub fn #map_ident(&self) #return_type {
+                ::core::clone::Clone::clone(&self.0).#map_ident()
+            }
+        }
+    };
+    let doc = format!("A [`{0}`] exported to JavaScript.", enum_ident);
+
+    Some(quote! {
+        #[doc = #doc]
+        #[::napi_derive::napi(js_name = #js_name)]
+        pub struct #class(#enum_name);
+
+        #[::napi_derive::napi]
+        impl #class {
+            #(#factories)*
+            #map
+        }
+    })
+}
+
+/// `map_async` and `map_const`, which call the `async` and `const` functions respectively, and give the variant back
+/// if it corresponds to another function. `map_const` is only generated if `map` takes the `enum` by value, since the
+/// fields would have to be cloned otherwise (which can't be done in a `const fn`).
+pub fn split(
+    enum_name: &Type,
+    variants: &Variants,
+    functions: &Functions<'_>,
+    calls: &[Expr],
+    args: &Args,
+) -> TokenStream {
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let inline = inline(args);
+    let vis = args.nested_map_vis();
+    let unsafety = &functions.map_unsafety(args);
+    let parameters = Parameters::new(functions, args).0;
+    let output = output_type(&functions.return_type);
+    let (receiver, rest) = match (&args.by_ref, &args.by_mut) {
+        (Some(_), _) => (quote!(&self), quote!(&Self)),
+        (_, Some(_)) => (quote!(&mut self), quote!(&mut Self)),
+        _ => (quote!(self), quote!(Self)),
+    };
+    let arms = |included: fn(&Signature) -> bool| {
+        variants
+            .0
+            .iter()
+            .zip(&variants.1)
+            .zip(&functions.signatures)
+            .zip(calls)
+            .filter(move |((_, signature), _)| included(signature))
+            .map(|(((variant, structure), signature), call)| {
+                (
+                    cfg(variant),
+                    &variant.ident,
+                    bindings(variant, structure.as_ref(), signature),
+                    field_names(variant, signature),
+                    call,
+                )
+            })
+    };
+
+    let async_arms = arms(|signature| signature.asyncness.is_some()).map(
+        |(cfg, ident, bindings, _, call)| {
+            quote!(#cfg Self::#ident #bindings => ::core::result::Result::Ok(#call),)
+        },
+    );
+    let map_async = quote! {
+        #inline
+        #vis async #unsafety fn map_async(#receiver #(, #parameters)*) -> ::core::result::Result<#output, #rest> {
+            match self {
+                #(#async_arms)*
+                #[allow(unreachable_patterns)]
+                other => ::core::result::Result::Err(other),
+            }
+        }
+    };
+
+    // The `enum` may have fields that need to be dropped (those of the `async` functions), which a `const fn` can't do
+    // even for a variant that its fields were moved out of (since fields that are `Copy` are left in place). The fields
+    // are read out of the variant instead, and the `enum` is forgotten.
+    let map_const = args.borrow().is_none().then(|| {
+        let const_arms = arms(|signature| signature.constness.is_some()).map(
+            |(cfg, ident, bindings, names, call)| {
+                quote! {
+                    #cfg
+                    Self::#ident #bindings => {
+                        // SAFETY: Every field is read out of the variant exactly once, and the `enum` is forgotten so
+                        // that none of them are dropped twice.
+                        #(let #names = unsafe { ::core::ptr::read(#names) };)*
+                        ::core::mem::forget(self);
+                        ::core::result::Result::Ok(#call)
+                    }
+                }
+            },
+        );
+        quote! {
+            #inline
+            #vis const #unsafety fn map_const(self #(, #parameters)*) -> ::core::result::Result<#output, Self> {
+                match &self {
+                    #(#const_arms)*
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::result::Result::Err(self),
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics #enum_name #where_clause {
+            #map_async
+            #map_const
+        }
+    }
+}
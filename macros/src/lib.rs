@@ -0,0 +1,1179 @@
+/*!
+This crate contains the procedural macro attribute that is re-exported by `enum-from-functions`. See the documentation
+of that crate for more information.
+*/
+
+mod extract;
+/// Generates the code for the `enum` and the other items. The generated code refers to items through `::core` rather
+/// than `::std` (unless they have to be allocated), so that it can be used in `#![no_std]` crates.
+mod generate;
+
+use convert_case::{Case, Casing};
+use proc_macro::TokenStream;
+use proc_macro_error::{abort, emit_error, proc_macro_error, set_dummy};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    ext::IdentExt, parse_macro_input, parse_quote, Attribute, ImplItem, Item, ItemImpl, ItemMod,
+    Meta, Token,
+};
+
+/**
+A procedural macro attribute that generates an `enum` based on the functions defined in the `impl` block it annotates.
+See the crate documentation for more information.
+*/
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn enum_from_functions(args: TokenStream, input: TokenStream) -> TokenStream {
+    expand(args, input, None)
+}
+
+/**
+A function-like counterpart to the `enum_from_functions` macro attribute, which takes the `impl` block (or module) along
+with the macro attribute on it. See the crate documentation for more information.
+*/
+#[proc_macro_error]
+#[proc_macro]
+pub fn enum_from_functions_inline(input: TokenStream) -> TokenStream {
+    let mut item = parse_macro_input!(input as Item);
+    let attributes = match &mut item {
+        Item::Impl(item) => &mut item.attrs,
+        Item::Mod(item) => &mut item.attrs,
+        item => abort!(item, "expected an `impl` block or a module"),
+    };
+
+    // The arguments are taken from the macro attribute, which is optional.
+    let mut args = proc_macro2::TokenStream::new();
+    if let Some(index) = attributes
+        .iter()
+        .position(|attribute| attribute.path().is_ident("enum_from_functions"))
+    {
+        let attribute = attributes.remove(index);
+        match attribute.meta {
+            Meta::Path(_) => (),
+            Meta::List(list) => args = list.tokens,
+            Meta::NameValue(_) => abort!(attribute, "expected `#[enum_from_functions(...)]`"),
+        }
+    }
+
+    expand(args.into(), quote!(#item).into(), None)
+}
+
+/// Passes the input of the macro attribute along the macros defined by the `impl` blocks given to `extended_by`,
+/// collecting their functions, and then expands it once they have all been called.
+#[doc(hidden)]
+#[proc_macro_error]
+#[proc_macro]
+pub fn extend(input: TokenStream) -> TokenStream {
+    let extract::Extension {
+        block,
+        remaining,
+        mut collected,
+        args,
+        input,
+    } = parse_macro_input!(input as extract::Extension);
+    collected.push(block);
+    if remaining.is_empty() {
+        expand(args.into(), input.into(), Some(collected))
+    } else {
+        extract::extended_by(&remaining, &collected, &args, &input).into()
+    }
+}
+
+/// Generates the `enum` and the other items for an `impl` block (or module), which is shared by both forms of the macro.
+/// The `impl` blocks marked with `extend` that are given to `extended_by` are passed in once they have been collected.
+fn expand(args: TokenStream, input: TokenStream, extensions: Option<Vec<ItemImpl>>) -> TokenStream {
+    // If the expansion fails, the input is still emitted alongside the errors so that code using its functions doesn't
+    // report errors of its own. A declaration of the `enum` is added to it once the variants are known.
+    set_dummy(generate::dummy(&input));
+
+    let tokens = proc_macro2::TokenStream::from(args.clone());
+    let mut args = match syn::parse::<extract::Args>(args) {
+        Ok(args) => args,
+        Err(errors) => {
+            for err in errors {
+                emit_error!(err.span(), err);
+            }
+            extract::Args::default()
+        }
+    };
+
+    // The functions of an `impl` block marked with `extend` are added to the `enum` by the `impl` block that generates
+    // it, so the block itself is passed through along with a macro that the other block calls to collect them.
+    if let Some((_, name)) = &args.extend {
+        let mut input = parse_macro_input!(input as ItemImpl);
+        let extension = extract::extension(&input, name);
+        extract::FunctionArgs::strip(&mut input);
+        generate::strip_parameter_attributes(&mut input);
+        return quote!(#input #extension).into();
+    }
+    // The input is handed to the macros given to `extended_by` first, the last of which expands it along with their
+    // functions.
+    let extensions = match (extensions, &args.extended_by) {
+        (None, Some((_, paths))) if !paths.is_empty() => {
+            return extract::extended_by(paths, &[], &tokens, &input.into()).into();
+        }
+        (extensions, _) => extensions.unwrap_or_default(),
+    };
+
+    // With the `is_sync` feature, `maybe_async` makes the functions (and therefore `map`) blocking.
+    let make_sync = args.maybe_async.is_some() && cfg!(feature = "is_sync");
+
+    // The macro attribute can also be placed on a module, in which case its free functions are used as if they were in
+    // an `impl` block for an `enum` named after the module. The generated items are placed inside of the module, as
+    // they are with the `module` argument.
+    let mut module = None;
+    let (parsed_input, attributes) = {
+        let mut parsed_input = match syn::parse::<ItemImpl>(input.clone()) {
+            Ok(parsed_input) => parsed_input,
+            Err(err) => match syn::parse::<ItemMod>(input) {
+                Ok(mut input) => {
+                    if let Some(arg) = &args.module {
+                        emit_error!(arg, "`module` cannot be used on a module");
+                    }
+                    if let Some((arg, _)) = &args.external {
+                        emit_error!(arg, "`external` cannot be used on a module");
+                    }
+                    if let (true, Some((_, items))) = (make_sync, &mut input.content) {
+                        for item in items {
+                            if let Item::Fn(function) = item {
+                                extract::make_sync(&mut function.sig, &mut function.block);
+                            }
+                        }
+                    }
+                    let parsed_input = extract::module_impl(&input);
+                    args.module = Some(input.ident.clone());
+                    module = Some(input);
+                    parsed_input
+                }
+                Err(_) => return err.to_compile_error().into(),
+            },
+        };
+        if make_sync {
+            extract::make_sync_impl(&mut parsed_input);
+        }
+        let mut attributes = parsed_input.attrs.clone();
+        parsed_input.attrs.clear();
+        if let Some((_, paths)) = &args.external {
+            extract::external(&mut parsed_input, paths);
+        }
+        if let Some(lifetime) = args.lifetime() {
+            extract::push_generic(&mut parsed_input, parse_quote!(#lifetime));
+        }
+        extract::lift_generics(&mut parsed_input);
+        // A C-compatible representation is used unless one is provided, which isn't possible for an empty `enum`.
+        if args.ffi.is_some()
+            && !attributes
+                .iter()
+                .any(|attribute| attribute.path().is_ident("repr"))
+            && parsed_input
+                .items
+                .iter()
+                .any(|item| matches!(item, syn::ImplItem::Fn(_)))
+        {
+            attributes.push(parse_quote!(#[repr(C)]));
+        }
+        // The derives come before the other attributes, which may be helper attributes of the derives.
+        let mut derives: Vec<Attribute> = Vec::new();
+        if args.serde.is_some() {
+            derives.push(parse_quote!(#[derive(::serde::Serialize, ::serde::Deserialize)]));
+        }
+        if args.schemars.is_some() {
+            derives.push(parse_quote!(#[derive(::schemars::JsonSchema)]));
+        }
+        if let Some((_, derive)) = &args.clap {
+            derives.push(parse_quote!(#[derive(::clap::#derive)]));
+        }
+        attributes.splice(0..0, derives);
+        (parsed_input, attributes)
+    };
+
+    // The functions of `impl` blocks marked with `extend` are only used to generate the `enum` and the other items,
+    // since they are already defined by those blocks. The same goes for the functions with their parameters renamed
+    // after their fields, and without their context parameter (which isn't stored in the variants).
+    let extended = {
+        let mut extended = parsed_input.clone();
+        extract::extend(&mut extended, extensions);
+        if args.clap.is_some() {
+            extract::clap_arguments(&mut extended);
+        }
+        extract::ParameterArgs::rename_fields(&mut extended);
+        if let Some(context) = &args.context {
+            extract::strip_context(&mut extended, context);
+        }
+        extended
+    };
+    let enum_name = &*parsed_input.self_ty;
+    let mut functions = match extract::Functions::try_from(&extended) {
+        Ok(functions) => functions,
+        Err(err) => abort!(err.span(), err),
+    };
+    functions.free = module.is_some();
+    // `map` can be made `async` even if none of the functions are, so that `async` functions can be added without
+    // changing its signature.
+    if let Some(force_async) = &args.force_async {
+        functions
+            .asyncness
+            .get_or_insert(Token![async](force_async.span()));
+    }
+    // Functions may have different return types only if `map` converts them into the same type, or if only `try_map` is
+    // generated.
+    let mut no_map = args.no_map.is_some();
+    if let Some(output) = generate::output_ident(enum_name, &args) {
+        functions.return_type = parse_quote!(-> #output);
+    } else if let Some(return_as) = &args.return_as {
+        functions.return_type = parse_quote!(-> #return_as);
+    } else if args.try_map.is_some() && !functions.return_types_match() {
+        no_map = true;
+    } else {
+        functions.check_return_types();
+    }
+    if let Some(return_type) = generate::box_return(&functions, &args) {
+        functions.return_type = return_type;
+    }
+    if let Some(memoize) = &args.memoize {
+        generate::check_memoize(&functions, memoize);
+    }
+    for arg in [
+        args.tracing.as_ref().map(|(tracing, _)| tracing),
+        args.log.as_ref(),
+        args.metrics.as_ref().map(|(metrics, _)| metrics),
+        args.custom.as_ref().map(|(custom, _)| custom),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if let Some(constness) = functions.map_constness(&args) {
+            emit_error!(
+                constness,
+                "`{}` requires that functions are not `const`",
+                arg
+            );
+        }
+    }
+    if args.unit_variants.is_some() {
+        if let Err(errors) = functions.shared_inputs() {
+            for err in errors {
+                emit_error!(err.span(), err);
+            }
+        }
+    }
+
+    // Unpack the struct here because we can't in the `quote` block.
+    let (
+        enum_vis,
+        map_vis,
+        return_type,
+        asyncness,
+        constness,
+        unsafety,
+        calls,
+        variants,
+        parameters,
+    ) = {
+        (
+            // If the generated items are placed in a separate module, their visibilities need to be adjusted.
+            args.nested_enum_vis(),
+            args.nested_map_vis(),
+            &functions.return_type,
+            functions.asyncness,
+            functions.map_constness(&args),
+            functions.map_unsafety(&args),
+            generate::Calls::new(&functions, &args).0,
+            generate::Variants::new(&functions, &args, &attributes),
+            generate::Parameters::new(&functions, &args).0,
+        )
+    };
+    let receiver = match (&args.by_ref, &args.by_mut) {
+        (Some(_), _) => quote!(&self),
+        (_, Some(_)) => quote!(&mut self),
+        _ => quote!(self),
+    };
+    // An empty `enum` can't be matched through a reference, so it is dereferenced instead.
+    let scrutinee = if args.borrow().is_some() && variants.0.is_empty() && !args.extra_variants() {
+        quote!(*self)
+    } else {
+        quote!(self)
+    };
+
+    let (impl_generics, _, where_clause) = functions.generics.split_for_impl();
+    let enum_ident = extract::enum_ident(enum_name);
+    let enum_generics = generate::enum_generics(enum_name, functions.generics);
+    let phantom = generate::phantom_arm(&variants, quote!(Self));
+
+    let output = {
+        let mut output = parsed_input.clone();
+        extract::FunctionArgs::strip(&mut output);
+        generate::strip_parameter_attributes(&mut output);
+        if args.private_functions.is_some() {
+            generate::make_private(&mut output);
+        }
+        // With `generate_tests`, the functions record that they were called in a marker defined by the module of
+        // tests.
+        if args.generate_tests.is_some() {
+            let tests = generate::tests_ident(enum_name);
+            let marker = match (&module, &args.module) {
+                (None, Some(module)) => quote!(self::#module::#tests::CALLED),
+                _ => quote!(self::#tests::CALLED),
+            };
+            for item in &mut output.items {
+                if let ImplItem::Fn(function) = item {
+                    generate::mark(&mut function.block, &function.sig, &marker);
+                }
+            }
+            if let Some((_, items)) = module.as_mut().and_then(|module| module.content.as_mut()) {
+                for item in items {
+                    if let Item::Fn(function) = item {
+                        generate::mark(&mut function.block, &function.sig, &marker);
+                    }
+                }
+            }
+        }
+        output
+    };
+
+    // The generated items are placed in a module of their own (if there is one), alongside the functions.
+    let place = |generated: proc_macro2::TokenStream,
+                 module: Option<ItemMod>,
+                 match_macro: Option<proc_macro2::TokenStream>| {
+        // The `enum` is re-exported from the parent module with its own visibility, so that it can be reached there.
+        let enum_vis = args.enum_vis();
+        if let Some(mut module) = module {
+            let ident = module.ident.clone();
+            generate::fill_module(&mut module, generated, &args);
+            quote! {
+                #module
+                #enum_vis use self::#ident::#enum_ident;
+                #match_macro
+            }
+        } else if let Some(module) = &args.module {
+            let module_vis = args.enum_vis();
+            quote! {
+                #module_vis mod #module {
+                    use super::*;
+
+                    #generated
+                }
+                #enum_vis use self::#module::#enum_ident;
+                #match_macro
+
+                #output
+            }
+        } else {
+            quote! {
+                #generated
+                #match_macro
+
+                #output
+            }
+        }
+    };
+    set_dummy(place(
+        generate::dummy_enum(&variants, &functions, &args, &attributes),
+        module.clone(),
+        None,
+    ));
+
+    let variants_iter = variants
+        .0
+        .iter()
+        .map(ToTokens::to_token_stream)
+        .chain(generate::extra_variants(&functions, &parameters, &args))
+        .chain(variants.2.iter().map(ToTokens::to_token_stream));
+    let arms = generate::arms(&variants, &functions, calls.iter());
+
+    let non_exhaustive = args
+        .non_exhaustive
+        .as_ref()
+        .map(|non_exhaustive| quote!(#[#non_exhaustive]));
+    let wildcard = args.wildcard.as_ref().map(|wildcard| {
+        quote! {
+            #[allow(unreachable_patterns)]
+            _ => #wildcard,
+        }
+    });
+
+    extract::check_names(&extended, &args, !no_map);
+    let map = if !no_map {
+        let map_ident = args.map_ident();
+        let nested = generate::extra_arms(&functions, &parameters, &map_ident, &args, |call| call);
+        // With `jump_table`, the function is called through a table of pointers instead of being matched on directly.
+        let (jump_table, body) = match args.jump_table.as_ref().and_then(|jump_table| {
+            generate::jump_table(enum_name, &variants, &functions, jump_table, &args)
+        }) {
+            Some((jump_table, body)) => (Some(jump_table), body),
+            None => (
+                None,
+                quote! {
+                    match #scrutinee {
+                        #(#arms)*
+                        #nested
+                        #phantom
+                        #wildcard
+                    }
+                },
+            ),
+        };
+
+        // A boxed future is returned in place of an `async fn`, which can't be `const` either.
+        let (qualifiers, output_type) = {
+            let output_type = generate::output_type(return_type);
+            if args.boxed_future.is_some() {
+                let lifetime = args.borrow().map(|_| quote!(+ '_));
+                (
+                    quote!(#unsafety),
+                    quote! {
+                        ::core::pin::Pin<
+                            ::enum_from_functions::__private::Box<
+                                dyn ::core::future::Future<Output = #output_type> + ::core::marker::Send #lifetime
+                            >
+                        >
+                    },
+                )
+            } else {
+                (quote!(#asyncness #constness #unsafety), output_type)
+            }
+        };
+        let output = quote!(-> #output_type);
+        let body = if args.boxed_future.is_some() {
+            quote!(::enum_from_functions::__private::Box::pin(async move { #body }))
+        } else {
+            body
+        };
+
+        let inline = generate::inline(&args);
+
+        // The free functions simply forward to `map`.
+        let forward = |name: &syn::Ident, qualifiers: proc_macro2::TokenStream| {
+            let reference = match (&args.by_ref, &args.by_mut) {
+                (Some(_), _) => quote!(&),
+                (_, Some(_)) => quote!(&mut),
+                _ => quote!(),
+            };
+            let parameter_names = parameters.iter().map(|parameter| &parameter.pat);
+            let mut call = quote!(__enum.#map_ident(#(#parameter_names),*));
+            if asyncness.is_some() && args.boxed_future.is_none() {
+                call = quote!(#call.await);
+            }
+            if unsafety.is_some() {
+                call = quote!(unsafe { #call });
+            }
+
+            quote! {
+                #inline
+                #enum_vis #qualifiers fn #name #impl_generics (__enum: #reference #enum_name #(, #parameters)*) #output #where_clause {
+                    #call
+                }
+            }
+        };
+        let snake_name = extract::enum_ident(enum_name)
+            .unraw()
+            .to_string()
+            .to_case(Case::Snake);
+        let free_fn = args.free_fn.as_ref().map(|(_, name)| {
+            let name = name
+                .clone()
+                .unwrap_or_else(|| format_ident!("map_{}", snake_name));
+            forward(&name, qualifiers.clone())
+        });
+
+        // An `extern "C"` function can't be `async` or `const`.
+        let ffi = args.ffi.as_ref().and_then(|(ffi, name)| {
+            if let (Some(asyncness), None) = (asyncness, &args.boxed_future) {
+                emit_error!(
+                    asyncness,
+                    "`{}` requires `boxed_future` when functions are `async`",
+                    ffi
+                );
+                return None;
+            }
+
+            let name = name
+                .clone()
+                .unwrap_or_else(|| format_ident!("extern_map_{}", snake_name));
+            Some(forward(&name, quote!(#unsafety extern "C")))
+        });
+
+        // `Dispatch::dispatch` and the closure returned by `into_fn` are safe and synchronous, and can't return a
+        // future that borrows from themselves, so only some configurations of `map` can be forwarded to them.
+        let forwardable = |arg: &syn::Ident| {
+            let mut valid = true;
+            if let Some(unsafety) = unsafety {
+                emit_error!(
+                    unsafety,
+                    "`{}` requires that functions are not `unsafe`",
+                    arg
+                );
+                valid = false;
+            }
+            if let (Some(asyncness), None) = (asyncness, &args.boxed_future) {
+                emit_error!(
+                    asyncness,
+                    "`{}` requires `boxed_future` when functions are `async`",
+                    arg
+                );
+                valid = false;
+            }
+            if let (Some(borrow), Some(boxed_future)) = (args.borrow(), &args.boxed_future) {
+                emit_error!(
+                    borrow,
+                    "cannot combine `{}` and `{}` with `{}`",
+                    borrow,
+                    boxed_future,
+                    arg
+                );
+                valid = false;
+            }
+            valid
+        };
+
+        // `Dispatch::dispatch`, `DynDispatch` and the batch functions call `map` without passing on any parameters.
+        let parameterless = |arg: &syn::Ident| match (&args.unit_variants, parameters.is_empty()) {
+            (Some(unit_variants), false) => {
+                emit_error!(
+                    unit_variants,
+                    "`{}` requires that `map` does not take any parameters",
+                    arg
+                );
+                false
+            }
+            _ => true,
+        };
+
+        // `Dispatch::dispatch` also takes nothing other than `self`.
+        let dispatch = args.dispatch.as_ref().and_then(|dispatch| {
+            let mut valid = forwardable(dispatch);
+            valid &= parameterless(dispatch);
+
+            let mutability = args.by_mut.as_ref().map(|_| quote!(mut));
+            valid.then(|| {
+                quote! {
+                    impl #impl_generics ::enum_from_functions::Dispatch for #enum_name #where_clause {
+                        type Output = #output_type;
+
+                        #inline
+                        fn dispatch(#mutability self) -> Self::Output {
+                            self.#map_ident()
+                        }
+                    }
+                }
+            })
+        });
+
+        // `map_batch` calls `map` on each variant in turn (awaiting each call if it is `async`), and can't pass on any
+        // parameters.
+        let map_batch = args.map_batch.as_ref().and_then(|map_batch| {
+            if !parameterless(map_batch) {
+                return None;
+            }
+
+            let output = generate::output_type(return_type);
+            let mut call = quote!(variant.#map_ident());
+            if asyncness.is_some() {
+                call = quote!(#call.await);
+            }
+            if unsafety.is_some() {
+                call = quote!(unsafe { #call });
+            }
+
+            Some(quote! {
+                /// Calls the function that corresponds to each of the variants in order, collecting the results.
+                #inline
+                #map_vis #asyncness #unsafety fn map_batch(
+                    variants: impl ::core::iter::IntoIterator<Item = Self>,
+                ) -> ::enum_from_functions::__private::Vec<#output> {
+                    let mut outputs = ::enum_from_functions::__private::Vec::new();
+                    for variant in variants {
+                        outputs.push(#call);
+                    }
+                    outputs
+                }
+            })
+        });
+
+        // `map_batch_par` calls `map` on each variant on the thread pool of `rayon`, so `map` can't be `async` either.
+        let map_batch_par = args.map_batch_par.as_ref().and_then(|map_batch_par| {
+            let mut valid = true;
+            if let Some(asyncness) = asyncness {
+                emit_error!(
+                    asyncness,
+                    "`{}` requires that functions are not `async`",
+                    map_batch_par
+                );
+                valid = false;
+            }
+            valid &= parameterless(map_batch_par);
+
+            let output = generate::output_type(return_type);
+            // The variants are shared between the threads when `map` borrows them.
+            let (variants, bounds) = match (&args.by_ref, &args.by_mut) {
+                (Some(_), _) => (quote!(&[Self]), quote!(::core::marker::Sync)),
+                (_, Some(_)) => (quote!(&mut [Self]), quote!(::core::marker::Send)),
+                _ => (quote!(::enum_from_functions::__private::Vec<Self>), quote!(::core::marker::Send)),
+            };
+            let mut call = quote!(variant.#map_ident());
+            if unsafety.is_some() {
+                call = quote!(unsafe { #call });
+            }
+
+            valid.then(|| {
+                quote! {
+                    /// Calls the function that corresponds to each of the variants in parallel, collecting the results in
+                    /// order.
+                    #inline
+                    #map_vis #unsafety fn map_batch_par(variants: #variants) -> ::enum_from_functions::__private::Vec<#output>
+                    where
+                        Self: #bounds,
+                        #output: ::core::marker::Send,
+                    {
+                        ::rayon::iter::ParallelIterator::collect(::rayon::iter::ParallelIterator::map(
+                            ::rayon::iter::IntoParallelIterator::into_par_iter(variants),
+                            |variant| #call,
+                        ))
+                    }
+                }
+            })
+        });
+
+        // `map_batch_concurrent` spawns a task for each call on the runtime of `tokio`, which has to own the variant.
+        let map_batch_concurrent = args.map_batch_concurrent.as_ref().and_then(|map_batch_concurrent| {
+            let mut valid = true;
+            if asyncness.is_none() {
+                emit_error!(
+                    map_batch_concurrent,
+                    "`{}` requires that functions are `async`",
+                    map_batch_concurrent
+                );
+                valid = false;
+            }
+            if let Some(unsafety) = unsafety {
+                emit_error!(
+                    unsafety,
+                    "`{}` requires that functions are not `unsafe`",
+                    map_batch_concurrent
+                );
+                valid = false;
+            }
+            valid &= parameterless(map_batch_concurrent);
+
+            let output = generate::output_type(return_type);
+
+            valid.then(|| {
+                quote! {
+                    /// Calls the function that corresponds to each of the variants in a task of its own, running at most
+                    /// `limit` of them at once, and collects the results in order.
+                    #map_vis async fn map_batch_concurrent(
+                        variants: impl ::core::iter::IntoIterator<Item = Self>,
+                        limit: usize,
+                    ) -> ::enum_from_functions::__private::Vec<#output>
+                    where
+                        Self: ::core::marker::Send + 'static,
+                        #output: ::core::marker::Send + 'static,
+                    {
+                        let mut tasks = ::tokio::task::JoinSet::new();
+                        let mut outputs = ::enum_from_functions::__private::Vec::new();
+                        let mut join = |result: ::core::result::Result<_, ::tokio::task::JoinError>| match result {
+                            ::core::result::Result::Ok(output) => outputs.push(output),
+                            ::core::result::Result::Err(err) => ::std::panic::resume_unwind(err.into_panic()),
+                        };
+                        for (index, variant) in ::core::iter::Iterator::enumerate(
+                            ::core::iter::IntoIterator::into_iter(variants),
+                        ) {
+                            // Once the limit is reached, another task has to finish before the next one is spawned.
+                            if tasks.len() >= ::core::cmp::max(limit, 1) {
+                                if let ::core::option::Option::Some(result) = tasks.join_next().await {
+                                    join(result);
+                                }
+                            }
+                            tasks.spawn(async move { (index, variant.#map_ident().await) });
+                        }
+                        while let ::core::option::Option::Some(result) = tasks.join_next().await {
+                            join(result);
+                        }
+
+                        outputs.sort_by_key(|(index, _)| *index);
+                        ::core::iter::Iterator::collect(::core::iter::Iterator::map(
+                            ::core::iter::IntoIterator::into_iter(outputs),
+                            |(_, output)| output,
+                        ))
+                    }
+                }
+            })
+        });
+
+        // `DynDispatch::dispatch` returns a boxed future (whether or not `map` is `async`) that owns the `enum`, so
+        // it can't be generic.
+        let dyn_dispatch = args.dyn_dispatch.as_ref().and_then(|dyn_dispatch| {
+            let mut valid = true;
+            if let Some(param) = functions.generics.params.first() {
+                emit_error!(
+                    param,
+                    "`{}` is not supported for generic `impl` blocks",
+                    dyn_dispatch
+                );
+                valid = false;
+            }
+            if let Some(unsafety) = unsafety {
+                emit_error!(
+                    unsafety,
+                    "`{}` requires that functions are not `unsafe`",
+                    dyn_dispatch
+                );
+                valid = false;
+            }
+            valid &= parameterless(dyn_dispatch);
+
+            let output = generate::output_type(return_type);
+            let mutability = args.by_mut.as_ref().map(|_| quote!(mut));
+            let call = match asyncness {
+                Some(_) => quote!((*self).#map_ident().await),
+                None => quote!((*self).#map_ident()),
+            };
+            valid.then(|| {
+                quote! {
+                    impl ::enum_from_functions::DynDispatch for #enum_name {
+                        type Output = #output;
+
+                        fn dispatch(
+                            #mutability self: ::enum_from_functions::__private::Box<Self>,
+                        ) -> ::core::pin::Pin<
+                            ::enum_from_functions::__private::Box<dyn ::core::future::Future<Output = Self::Output> + ::core::marker::Send>,
+                        > {
+                            ::enum_from_functions::__private::Box::pin(async move { #call })
+                        }
+                    }
+                }
+            })
+        });
+
+        // The closure captures the `enum`, and is only as restrictive as `map`'s receiver requires.
+        let into_fn = args
+            .into_fn
+            .as_ref()
+            .filter(|into_fn| forwardable(into_fn))
+            .map(|_| {
+                let closure = match (&args.by_ref, &args.by_mut) {
+                    (Some(_), _) => quote!(Fn),
+                    (_, Some(_)) => quote!(FnMut),
+                    _ => quote!(FnOnce),
+                };
+                let mutability = args.by_mut.as_ref().map(|_| quote!(mut));
+                let parameter_types = parameters.iter().map(|parameter| &parameter.ty);
+                let parameter_names = parameters.iter().map(|parameter| &parameter.pat);
+                let parameter_names_again = parameter_names.clone();
+
+                quote! {
+                    /// Converts the variant into a closure that forwards to `map`.
+                    #inline
+                    #map_vis fn into_fn(#mutability self) -> impl #closure(#(#parameter_types),*) -> #output_type {
+                        move |#(#parameter_names),*| self.#map_ident(#(#parameter_names_again),*)
+                    }
+                }
+            });
+
+        Some(quote! {
+            impl #impl_generics #enum_name #where_clause {
+                #inline
+                #map_vis #qualifiers fn #map_ident(#receiver #(, #parameters)*) #output {
+                    #body
+                }
+
+                #into_fn
+
+                #map_batch
+
+                #map_batch_par
+
+                #map_batch_concurrent
+            }
+
+            #jump_table
+
+            #dispatch
+
+            #dyn_dispatch
+
+            #free_fn
+
+            #ffi
+        })
+    } else {
+        None
+    };
+
+    // `map_ref` is generated in the same way as `map` would be with `by_ref`.
+    let map_ref = args.map_ref.as_ref().map(|map_ref| {
+        let args = extract::Args {
+            by_ref: Some(map_ref.clone()),
+            ..args.clone()
+        };
+        let constness = functions.map_constness(&args);
+        let calls = generate::Calls::new(&functions, &args).0;
+        let arms = generate::arms(&variants, &functions, calls.iter());
+        let scrutinee = if variants.0.is_empty() && !args.extra_variants() {
+            quote!(*self)
+        } else {
+            quote!(self)
+        };
+        let nested = generate::extra_arms(&functions, &parameters, map_ref, &args, |call| call);
+        let inline = generate::inline(&args);
+
+        quote! {
+            impl #impl_generics #enum_name #where_clause {
+                #inline
+                #map_vis #asyncness #constness #unsafety fn map_ref(&self #(, #parameters)*) #return_type {
+                    match #scrutinee {
+                        #(#arms)*
+                        #nested
+                        #phantom
+                        #wildcard
+                    }
+                }
+            }
+        }
+    });
+
+    // When `async` and `const` functions are mixed (so `map` is `async` but not `const`), each of them can also be
+    // called through a function of its own.
+    let split = (functions.asyncness.is_some() && functions.constness.is_some() && !no_map)
+        .then(|| generate::split(enum_name, &variants, &functions, &calls, &args));
+
+    // `try_map` is generated in the same way as `map`, converting the error returned by each function.
+    let try_map = args.try_map.as_ref().and_then(|error| {
+        let ok = generate::ok_type(&functions)?;
+        let map_err = |call| quote!(::core::result::Result::map_err(#call, ::core::convert::Into::into));
+        let nested = generate::extra_arms(&functions, &parameters, &args.map_ident(), &args, map_err);
+        let calls = calls.iter().map(|call| map_err(quote!(#call)));
+        let arms = generate::arms(&variants, &functions, calls);
+        let inline = generate::inline(&args);
+
+        Some(quote! {
+            impl #impl_generics #enum_name #where_clause {
+                /// Calls the function that corresponds to the variant, converting the error that it returns.
+                #inline
+                #map_vis #asyncness #unsafety fn try_map(#receiver #(, #parameters)*) -> ::core::result::Result<#ok, #error> {
+                    match #scrutinee {
+                        #(#arms)*
+                        #nested
+                        #phantom
+                    }
+                }
+            }
+        })
+    });
+
+    // `map_catch` is generated in the same way as `map`, calling each function inside of `catch_unwind`.
+    let map_catch = args.map_catch.as_ref().and_then(|map_catch| {
+        if let Some(asyncness) = asyncness {
+            emit_error!(
+                asyncness,
+                "`{}` requires that functions are not `async`",
+                map_catch
+            );
+            return None;
+        }
+
+        let output_type = generate::output_type(return_type);
+        let catch_unwind = |call| {
+            quote!(::std::panic::catch_unwind(::core::panic::AssertUnwindSafe(move || #call)))
+        };
+        let nested =
+            generate::extra_arms(&functions, &parameters, &args.map_ident(), &args, catch_unwind);
+        let calls = calls.iter().map(|call| catch_unwind(quote!(#call)));
+        let arms = generate::arms(&variants, &functions, calls);
+        let inline = generate::inline(&args);
+
+        Some(quote! {
+            impl #impl_generics #enum_name #where_clause {
+                /// Calls the function that corresponds to the variant, returning the payload of the panic if it panics.
+                #inline
+                #map_vis #unsafety fn map_catch(#receiver #(, #parameters)*) -> ::core::result::Result<
+                    #output_type,
+                    ::enum_from_functions::__private::Box<dyn ::core::any::Any + ::core::marker::Send>,
+                > {
+                    match #scrutinee {
+                        #(#arms)*
+                        #nested
+                        #phantom
+                    }
+                }
+            }
+        })
+    });
+
+    // `map_timed` is generated in the same way as `map`, measuring how long the call takes.
+    let map_timed = args.timed.as_ref().map(|_| {
+        let nested = generate::extra_arms(&functions, &parameters, &args.map_ident(), &args, |call| call);
+        let output_type = generate::output_type(return_type);
+        let arms = generate::arms(&variants, &functions, calls.iter());
+        let inline = generate::inline(&args);
+
+        quote! {
+            impl #impl_generics #enum_name #where_clause {
+                /// Calls the function that corresponds to the variant, returning how long the call took alongside its
+                /// result.
+                #inline
+                #map_vis #asyncness #unsafety fn map_timed(#receiver #(, #parameters)*) -> (#output_type, ::core::time::Duration) {
+                    let start = ::std::time::Instant::now();
+                    let output = match #scrutinee {
+                        #(#arms)*
+                        #nested
+                        #phantom
+                        #wildcard
+                    };
+                    (output, start.elapsed())
+                }
+            }
+        }
+    });
+
+    let display = args
+        .display
+        .as_ref()
+        .map(|_| generate::display(enum_name, &variants, &functions, &args));
+
+    let debug = args
+        .debug
+        .as_ref()
+        .map(|_| generate::debug(enum_name, &variants, &functions, &args));
+
+    let from_str = args
+        .from_str
+        .as_ref()
+        .or(args.strum_like.as_ref())
+        .filter(|from_str| generate::fieldless(&variants, &functions, from_str))
+        .map(|_| generate::from_str(enum_name, &variants, &functions, &args));
+
+    let dispatch_by_name = args.dispatch_by_name.as_ref().and_then(|dispatch_by_name| {
+        generate::dispatch_by_name(enum_name, &functions, dispatch_by_name, &args)
+    });
+
+    let registry = args
+        .registry
+        .as_ref()
+        .and_then(|registry| generate::registry(enum_name, &functions, registry, &args));
+
+    // The error type is shared by all of the above.
+    let parse_error = (from_str.is_some() || dispatch_by_name.is_some() || registry.is_some())
+        .then(|| generate::parse_error(enum_name, &args));
+
+    let iter = args
+        .variants
+        .as_ref()
+        .or(args.strum_like.as_ref())
+        .filter(|variants_arg| generate::fieldless(&variants, &functions, variants_arg))
+        .map(|_| generate::iter(enum_name, &variants, &functions, &args));
+
+    let enum_map = args
+        .enum_map
+        .as_ref()
+        .filter(|enum_map| generate::fieldless(&variants, &functions, enum_map))
+        .and_then(|enum_map| generate::enum_map(enum_name, &variants, &functions, enum_map));
+
+    let into_static_str = args
+        .strum_like
+        .as_ref()
+        .map(|_| generate::into_static_str(enum_name, &variants, &functions, &args));
+
+    let arbitrary = args
+        .arbitrary
+        .as_ref()
+        .map(|_| generate::arbitrary(enum_name, &variants, &functions, &args));
+
+    let default = generate::default(enum_name, &variants, &functions, &args);
+
+    let count = args
+        .count
+        .as_ref()
+        .map(|_| generate::count(enum_name, &functions, &args));
+
+    let ordering = args
+        .ordering
+        .as_ref()
+        .map(|_| generate::ordering(enum_name, &variants, &functions, &args));
+
+    let name = args
+        .name
+        .as_ref()
+        .map(|_| generate::name_fn(enum_name, &variants, &functions, &args));
+
+    let names = args
+        .names
+        .as_ref()
+        .map(|_| generate::names(enum_name, &functions, &args));
+
+    let constructors = args
+        .constructors
+        .as_ref()
+        .map(|_| generate::constructors(enum_name, &variants, &functions, &args));
+
+    let kind = args
+        .kind
+        .as_ref()
+        .map(|_| generate::kind(enum_name, &variants, &functions, &args));
+
+    let accessors = args
+        .accessors
+        .as_ref()
+        .map(|_| generate::accessors(enum_name, &variants, &functions, &args));
+
+    let visitor = args
+        .visitor
+        .as_ref()
+        .map(|_| generate::visitor(enum_name, &variants, &functions, &args));
+
+    let repr_conversions = args
+        .repr_conversions
+        .as_ref()
+        .filter(|repr_conversions| generate::fieldless(&variants, &functions, repr_conversions))
+        .filter(|repr_conversions| {
+            // The hidden variant holding unused generic parameters has fields, so the `enum` can't be cast.
+            if variants.2.is_some() {
+                emit_error!(
+                    repr_conversions,
+                    "`repr_conversions` requires that every generic parameter is used by a function parameter"
+                );
+            }
+            variants.2.is_none()
+        })
+        .and_then(|repr_conversions| match extract::repr(&attributes) {
+            Some(repr) => Some(generate::repr_conversions(
+                enum_name, &variants, &functions, &repr, &args,
+            )),
+            None => {
+                emit_error!(
+                    repr_conversions,
+                    "`repr_conversions` requires a `#[repr(...)]` attribute with an integer type"
+                );
+                None
+            }
+        });
+
+    let as_fn = args
+        .as_fn
+        .as_ref()
+        .and_then(|as_fn| generate::as_fn(enum_name, &variants, &functions, as_fn, &args));
+
+    let map_all = args
+        .map_all
+        .as_ref()
+        .and_then(|map_all| generate::map_all(enum_name, &calls, &functions, map_all, &args));
+
+    let arg_structs = variants.1.iter().flatten();
+
+    // The macro is defined outside of the module (if any), since a `macro_rules!` macro can't be used outside of the
+    // module that it is defined in.
+    let match_macro = args.match_macro.as_ref().and_then(|(match_macro, _)| {
+        generate::match_macro(enum_name, &variants, &functions, match_macro, &args)
+    });
+
+    let dispatch_json = args.dispatch_json.as_ref().and_then(|dispatch_json| {
+        generate::dispatch_json(enum_name, &variants, &functions, dispatch_json, &args)
+    });
+
+    let wasm = args.wasm.as_ref().and_then(|wasm| {
+        if no_map {
+            emit_error!(wasm, "`wasm` requires that `map` is generated");
+            return None;
+        }
+        generate::wasm(enum_name, &variants, &functions, wasm, &args)
+    });
+    let pyo3 = args.pyo3.as_ref().and_then(|pyo3| {
+        if no_map {
+            emit_error!(pyo3, "`pyo3` requires that `map` is generated");
+            return None;
+        }
+        generate::pyo3(enum_name, &variants, &functions, pyo3, &args)
+    });
+    let napi = args.napi.as_ref().and_then(|napi| {
+        if no_map {
+            emit_error!(napi, "`napi` requires that `map` is generated");
+            return None;
+        }
+        generate::napi(enum_name, &variants, &functions, napi, &args)
+    });
+
+    let output_enum = generate::output_enum(enum_name, &functions, &args);
+    let caches = generate::caches(&functions, &args);
+
+    // Only the functions of this `impl` block can be marked, so those added by `extend` aren't tested.
+    let tests = args.generate_tests.as_ref().and_then(|generate_tests| {
+        if no_map {
+            emit_error!(
+                generate_tests,
+                "`generate_tests` requires that `map` is generated"
+            );
+            return None;
+        }
+        let local = parsed_input
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                ImplItem::Fn(function) => Some(function.sig.ident.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        generate::tests(
+            enum_name,
+            &variants,
+            &functions,
+            &local,
+            generate_tests,
+            &args,
+        )
+    });
+
+    // `clap` uses the documentation of the `enum` as the help text of the command, so it isn't summarized.
+    let summary = args
+        .clap
+        .is_none()
+        .then(|| generate::summary(enum_name, &variants, &functions, &attributes));
+
+    let generated = quote! {
+        #(#arg_structs)*
+        #output_enum
+
+        #(#attributes)*
+        #summary
+        #non_exhaustive
+        #enum_vis enum #enum_ident #enum_generics #where_clause {
+            #(#variants_iter,)*
+        }
+
+        #caches
+        #map
+        #map_ref
+        #split
+        #try_map
+        #map_catch
+        #map_timed
+        #display
+        #debug
+        #default
+        #parse_error
+        #from_str
+        #dispatch_by_name
+        #registry
+        #iter
+        #into_static_str
+        #enum_map
+        #count
+        #ordering
+        #name
+        #names
+        #constructors
+        #kind
+        #accessors
+        #visitor
+        #repr_conversions
+        #as_fn
+        #map_all
+        #arbitrary
+        #dispatch_json
+        #wasm
+        #pyo3
+        #napi
+        #tests
+    };
+
+    place(generated, module, match_macro).into()
+}
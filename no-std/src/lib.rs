@@ -0,0 +1,109 @@
+//! Checks that the code generated by the macro compiles in a `#![no_std]` crate. New arguments that don't need `std`
+//! should be added here.
+
+#![no_std]
+
+use enum_from_functions::enum_from_functions;
+
+#[enum_from_functions(
+    pub,
+    by_ref,
+    display,
+    debug,
+    from_str,
+    variants,
+    strum_like,
+    count,
+    name,
+    names,
+    kind,
+    accessors,
+    constructors,
+    dispatch,
+    dispatch_by_name,
+    map_all,
+    as_fn,
+    jump_table,
+    ffi,
+    free_fn,
+    into_fn,
+    match_macro
+)]
+#[derive(Clone, PartialEq)]
+impl Unit {
+    fn a() -> i32 {
+        1
+    }
+    #[enum_from_functions(default)]
+    fn b() -> i32 {
+        2
+    }
+}
+
+#[enum_from_functions(pub, map_ref, debug, accessors, constructors, kind, lifetime, visitor)]
+impl<T: Clone + core::fmt::Debug> Fields<T> {
+    fn value(value: T, name: &str) -> T {
+        let _ = name;
+        value
+    }
+    fn other(#[arg(default = 1)] value: u8, pair: (u8, u8)) -> T {
+        let _ = (value, pair);
+        unimplemented!()
+    }
+}
+
+#[enum_from_functions(pub, arg_structs, output_enum, module = outputs)]
+impl Outputs {
+    fn number(value: u8) -> u8 {
+        value
+    }
+    fn nothing() {}
+}
+
+#[enum_from_functions(pub, tuple_variants, try_map = Error, non_exhaustive)]
+impl Fallible {
+    fn first(value: u8) -> Result<u8, u8> {
+        Ok(value)
+    }
+    fn second() -> Result<u8, ()> {
+        Err(())
+    }
+}
+
+pub struct Error;
+impl From<u8> for Error {
+    fn from(_: u8) -> Self {
+        Error
+    }
+}
+impl From<()> for Error {
+    fn from(_: ()) -> Self {
+        Error
+    }
+}
+
+#[enum_from_functions(pub, unit_variants, repr_conversions)]
+#[repr(u8)]
+impl Shared {
+    async fn first(value: u8) -> u8 {
+        value
+    }
+    async fn second(value: u8) -> u8 {
+        value + 1
+    }
+}
+
+#[enum_from_functions(pub, by_ref, constructors)]
+#[derive(Clone)]
+impl Allocated {
+    fn text(text: &str) -> usize {
+        text.len()
+    }
+    fn nested(inner: Self, next: Option<Self>) -> usize {
+        inner.map() + next.as_ref().map_or(0, Self::map)
+    }
+    #[enum_from_functions(boxed)]
+    fn large(values: [u8; 64]) -> usize {
+        values.len()
+    }
+}
@@ -51,6 +51,9 @@ impl Enum {
     }
 }
 ```
+The generated `enum` is documented with a list of its variants and the signatures of the functions that they
+correspond to, following any documentation on the `impl` block.
+
 The signatures of functions in the `impl` block may be different, so long as they all have the same return type.
 
 Note that `fn f() -> T` and `async fn f() -> T` are considered to return the same type, even though the latter
@@ -82,9 +85,12 @@ impl Enum {
 }
 ```
 `async`, `const` and `unsafe` functions are supported. The presence of any of these keywords will result in the
-generated `map` function having the same keyword. For this reason, `async` and `const` functions cannot be present in
-the same `impl` block (though `unsafe` functions can be present with either of the other two).
-```compile_fail
+generated `map` function having the same keyword. Since `map` can't be both `async` and `const`, it is only `async` when
+`async` and `const` functions are present in the same `impl` block, and a `map_const` function is generated to call the
+`const` functions (alongside a `map_async` function that calls the `async` functions). These give the variant back if
+it corresponds to another function, and `map_const` is only generated if `map` takes the `enum` by value. `unsafe`
+functions can be present with either of the other two.
+```
 # use enum_from_functions::enum_from_functions;
 #[enum_from_functions]
 impl Enum {
@@ -94,9 +100,181 @@ impl Enum {
     const fn bar() -> &'static str {
         "Bar"
     }
+}
+# fn main() {
+const BAR: Result<&str, Enum> = Enum::Bar.map_const();
+assert_eq!(BAR.ok(), Some("Bar"));
+assert!(Enum::Foo.map_const().is_err());
+# futures::executor::block_on(async {
+assert_eq!(Enum::Foo.map_async().await.ok(), Some("Foo"));
+assert_eq!(Enum::Bar.map().await, "Bar");
+# });
+# }
+```
+If the callers of `map` can't violate the safety requirements of the `unsafe` functions (such as requirements that the
+rest of the program upholds), the `assume_safe` argument will make `map` safe and place each call to an `unsafe`
+function in an `unsafe` block instead. Providing it is an assertion that every variant can be mapped safely, so it
+shouldn't be used for requirements on the parameters of the functions, since any value can be stored in the variants.
+```
+# use enum_from_functions::enum_from_functions;
+static mut COUNTER: u32 = 0;
 
-    // This would result in `async const map(...` which is not supported in Rust.
+#[enum_from_functions(assume_safe)]
+impl Counter {
+    /// # Safety
+    /// `COUNTER` must not be accessed from multiple threads (this program only has one).
+    unsafe fn increment() -> u32 {
+        COUNTER += 1;
+        COUNTER
+    }
+    fn zero() -> u32 {
+        0
+    }
+}
+# fn main() {
+assert_eq!(Counter::Increment.map(), 1);
+assert_eq!(Counter::Zero.map(), 0);
+# }
+```
+Functions with an ABI (such as `extern "C" fn`) are supported as well. The ABI isn't applied to `map`, which calls the
+functions like any other (see the `ffi` argument below for an `extern "C"` counterpart to `map`).
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl Enum {
+    extern "C" fn foo() -> i32 {
+        1337
+    }
+    fn bar() -> i32 {
+        0
+    }
+}
+# fn main() {
+assert_eq!(Enum::Foo.map(), 1337);
+# }
+```
+Parameters that are destructured by a pattern (or ignored with `_`) are stored in a field named after their position
+instead, such as `arg0`, which is passed to the function as a whole. If another parameter already has that name, it is
+followed by underscores until it doesn't.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl Enum {
+    fn sum((first, second): (i32, i32), _: bool) -> i32 {
+        first + second
+    }
+}
+# fn main() {
+assert_eq!(Enum::Sum { arg0: (1, 2), arg1: true }.map(), 3);
+# }
+```
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl Enum {
+    fn sum((first, second): (i32, i32), arg0: i32) -> i32 {
+        first + second + arg0
+    }
+}
+# fn main() {
+assert_eq!(Enum::Sum { arg0_: (1, 2), arg0: 3 }.map(), 6);
+# }
+```
+The names of the variants are converted from the names of the functions, so two functions can't have names that
+differ only by case or underscores (such as `foo_bar` and `fooBar`).
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+// Causes a compile error because both functions correspond to the variant `FooBar`.
+#[enum_from_functions]
+impl Enum {
+    fn foo_bar() {}
+    #[allow(non_snake_case)]
+    fn fooBar() {}
+}
+```
+Functions named with raw identifiers (such as `r#type`) are supported too, and their variants are named without the
+`r#` prefix.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(display)]
+impl Keyword {
+    fn r#type(r#in: u8) -> u8 {
+        r#in
+    }
+    fn r#match() -> u8 {
+        0
+    }
+}
+# fn main() {
+assert_eq!(Keyword::Type { r#in: 1 }.map(), 1);
+assert_eq!(Keyword::Match.to_string(), "match");
+# }
+```
+Only the name of a parameter bound with `mut` (or `ref`) is used for its field, since the binding is part of the
+function rather than of the value.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(tuple_variants)]
+impl Enum {
+    fn shout(mut text: String) -> String {
+        text.push('!');
+        text
+    }
+}
+# fn main() {
+assert_eq!(Enum::Shout(String::from("hello")).map(), "hello!");
+# }
+```
+A field can be given a different name from its parameter with `#[arg(field = "name")]` (or
+`#[enum_from_functions(field = "name")]`), which is useful when the name of the parameter is only meant to be used by
+the function itself.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl Query {
+    fn user(#[arg(field = "id")] i: u32) -> String {
+        format!("user {i}")
+    }
+}
+# fn main() {
+assert_eq!(Query::User { id: 1 }.map(), "user 1");
+# }
+```
+Parameters of the type of the `enum` itself (written as `Self` or by its name) are stored in a
+[`Box`](std::boxed::Box), since the `enum` would have an infinite size otherwise. The value is moved out of the box when
+the function is called. The same goes for references to the `enum` that are stored by value, and an `Option` of the
+`enum` is stored as an `Option` of a box. `Self` is replaced by the type of the `enum` in the types of any other fields.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+##[derive(Clone)]
+impl Expr {
+    fn value(value: i32) -> i32 {
+        value
+    }
+    fn negate(inner: Self) -> i32 {
+        -inner.map()
+    }
+    fn double(inner: &Self) -> i32 {
+        inner.clone().map() * 2
+    }
+    fn or_zero(inner: Option<Self>) -> i32 {
+        inner.map_or(0, Self::map)
+    }
+    fn sum(terms: Vec<Self>) -> i32 {
+        terms.into_iter().map(Self::map).sum()
+    }
 }
+# fn main() {
+let one = Expr::Value { value: 1 };
+let expr = Expr::Negate {
+    inner: Box::new(one.clone()),
+};
+assert_eq!(expr.map(), -1);
+assert_eq!(Expr::Double { inner: Box::new(one.clone()) }.map(), 2);
+assert_eq!(Expr::OrZero { inner: Some(Box::new(one.clone())) }.map(), 1);
+assert_eq!(Expr::Sum { terms: vec![one.clone(), one] }.map(), 2);
+# }
 ```
 You can also create an empty `enum` by not providing any functions in the `impl` block (though I'm not sure why you
 would want to do this).
@@ -105,6 +283,303 @@ would want to do this).
 #[enum_from_functions]
 impl EmptyEnum {}
 ```
+The type of the `impl` block can also be given by its path. The `enum` is declared by the last segment of the path
+where the macro is used, so the path has to lead there, and the rest of the generated code refers to the `enum` by the
+full path.
+```
+mod commands {
+    # use enum_from_functions::enum_from_functions;
+    #[enum_from_functions(pub)]
+    impl crate::commands::Expr {
+        fn value(value: i32) -> i32 {
+            value
+        }
+        fn negate(inner: Expr) -> i32 {
+            -inner.map()
+        }
+    }
+}
+# fn main() {
+use commands::Expr;
+let expr = Expr::Negate {
+    inner: Box::new(Expr::Value { value: 1 }),
+};
+assert_eq!(expr.map(), -1);
+# }
+```
+The `impl` block can be generic, in which case the generic parameters and `where` clause are applied to the `enum`
+and to every generated `impl` block. Any type or lifetime parameter that isn't used by the parameters of the functions
+is held by a hidden variant, which can never be constructed.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(display)]
+impl<T, U> Enum<T, U>
+where
+    T: std::fmt::Display,
+{
+    fn show(value: T) -> String {
+        value.to_string()
+    }
+    fn other() -> String {
+        String::from("other")
+    }
+}
+# fn main() {
+let value: Enum<i32, ()> = Enum::Show { value: 1337 };
+assert_eq!(value.map(), "1337");
+assert_eq!(Enum::<i32, ()>::Other.to_string(), "other");
+# }
+```
+The type and lifetime parameters of generic functions are moved onto the `impl` block in the same way, so that the
+variants can store values of those types. Each parameter must have a different name from those of the `impl` block
+and of the other functions.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl Enum {
+    fn show<T: std::fmt::Display>(value: T) -> String {
+        value.to_string()
+    }
+    fn other() -> String {
+        String::from("other")
+    }
+}
+# fn main() {
+let value: Enum<i32> = Enum::Show { value: 1337 };
+assert_eq!(value.map(), "1337");
+# }
+```
+Const parameters are moved as well if the parameters of the function use them (such as in `[u8; N]`).
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl Enum {
+    fn sum<const N: usize>(bytes: [u8; N]) -> u32 {
+        bytes.iter().map(|&byte| u32::from(byte)).sum()
+    }
+    fn other() -> u32 {
+        0
+    }
+}
+# fn main() {
+assert_eq!(Enum::Sum { bytes: [1, 2, 3] }.map(), 6);
+assert_eq!(Enum::<4>::Other.map(), 0);
+# }
+```
+The bounds in the `where` clauses of the functions are moved onto the `impl` block too, since `map` can only call
+the functions if they are satisfied.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl<T> Enum<T> {
+    fn pair<U>(first: T, second: U) -> String
+    where
+        T: std::fmt::Display,
+        U: std::fmt::Display,
+    {
+        format!("{first}, {second}")
+    }
+}
+# fn main() {
+let value = Enum::Pair {
+    first: 1,
+    second: "two",
+};
+assert_eq!(value.map(), "1, two");
+# }
+```
+The macro attribute can also be placed on a trait `impl` block, in which case `map` calls the functions through the
+trait (so the trait doesn't need to be in scope where `map` is used). The signatures of its functions are declared by
+the trait, so they can't be generic, and the bounds in their `where` clauses are copied onto the `impl` block instead.
+```
+mod commands {
+    pub trait Command {
+        fn start(speed: u32) -> String;
+        fn stop() -> String;
+    }
+}
+
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl commands::Command for Enum {
+    fn start(speed: u32) -> String {
+        format!("start at {speed}")
+    }
+    fn stop() -> String {
+        String::from("stop")
+    }
+}
+# fn main() {
+assert_eq!(Enum::Start { speed: 1 }.map(), "start at 1");
+assert_eq!(Enum::Stop.map(), "stop");
+# }
+```
+The functions of an `enum` can be split across several `impl` blocks by marking the additional blocks with the
+`extend` argument, which names a macro that the block defines (as in `extend = stop_commands`) and can't be combined
+with any other arguments. The `impl` block that generates the `enum` lists those macros in the `extended_by` argument,
+and their functions are added as variants after its own, in the order that they are listed. Since the macros are
+defined with `macro_rules!`, the additional blocks have to come before that `impl` block (in the same module or a
+parent of it), and their functions can't be generic. A block that isn't listed by any other block is reported as an
+unused macro.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(extend = stop_commands)]
+impl Command {
+    fn stop() -> String {
+        String::from("stop")
+    }
+}
+
+#[enum_from_functions(extended_by(stop_commands))]
+impl Command {
+    fn start(speed: u32) -> String {
+        format!("start at {speed}")
+    }
+}
+# fn main() {
+assert_eq!(Command::Start { speed: 1 }.map(), "start at 1");
+assert_eq!(Command::Stop.map(), "stop");
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(extend = stop_commands)]
+impl Other {
+    fn stop() {}
+}
+
+// Causes a compile error because the functions of `stop_commands` are for another type.
+#[enum_from_functions(extended_by(stop_commands))]
+impl Command {
+    fn start() {}
+}
+```
+Functions defined elsewhere can be added to the `enum` by giving their paths to the `external` argument. The macro
+can't see the signatures of those functions, so each one must be declared by a stub in the `impl` block with the same
+name and without a body, which is replaced by a function that forwards to it. Its parameters must be named.
+```
+mod io {
+    pub fn read_file(path: String) -> String {
+        format!("read {path}")
+    }
+}
+
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(external(crate::io::read_file))]
+impl Command {
+    fn read_file(path: String) -> String;
+    fn stop() -> String {
+        String::from("stop")
+    }
+}
+# fn main() {
+assert_eq!(Command::ReadFile { path: "a".into() }.map(), "read a");
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+mod io {
+    pub fn read_file(path: String) -> String {
+        format!("read {path}")
+    }
+}
+
+// Causes a compile error because no stub is declared for `read_file`.
+#[enum_from_functions(external(crate::io::read_file))]
+impl Command {
+    fn stop() -> String {
+        String::from("stop")
+    }
+}
+```
+Other generated `enum`s can be added to the `enum` with the `nest` argument, which adds a variant named after each of
+them that holds it. `map` delegates those variants to the `map` function of the nested `enum`, which has to take the
+same parameters and return the same type (and `map_ref`, `try_map`, `map_catch` and `timed` delegate them in the same
+way). This can't be combined with the arguments that generate code for each variant besides `map`, such as `display`.
+```
+mod git {
+    # use enum_from_functions::enum_from_functions;
+    #[enum_from_functions(pub)]
+    impl Git {
+        fn commit(message: String) -> String {
+            format!("commit {message}")
+        }
+    }
+}
+
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(nest(git::Git))]
+impl Command {
+    fn help() -> String {
+        String::from("help")
+    }
+}
+# fn main() {
+assert_eq!(Command::Help.map(), "help");
+assert_eq!(
+    Command::Git(git::Git::Commit { message: "a".into() }).map(),
+    "commit a"
+);
+# }
+```
+The `custom` argument adds a variant named `Custom` (or the name given) that holds a boxed closure, which `map` calls
+with its parameters in place of a function. This is `FnOnce` unless `map` takes `&self` or `&mut self`, in which case
+it is `Fn` or `FnMut` respectively. Like `nest`, this can't be combined with the arguments that generate code for each
+variant besides `map`, or with `map_ref`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(custom)]
+impl Command {
+    fn help() -> String {
+        String::from("help")
+    }
+}
+# fn main() {
+let name = String::from("deploy");
+assert_eq!(Command::Custom(Box::new(move || name)).map(), "deploy");
+# }
+```
+The macro attribute can also be placed on a module, in which case the `enum` is generated from the free functions in
+the module and is named after it in `PascalCase`. The generated items are placed inside of the module (as they are
+with the `module` argument described below), and the attributes of the module are applied to the `enum`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+#[derive(Debug)]
+mod commands {
+    pub fn start(speed: u32) -> String {
+        format!("start at {speed}")
+    }
+    pub fn stop() -> String {
+        String::from("stop")
+    }
+}
+# fn main() {
+assert_eq!(commands::Commands::Start { speed: 1 }.map(), "start at 1");
+assert_eq!(Commands::Stop.map(), "stop");
+# }
+```
+The [`enum_from_functions_inline!`] macro is a function-like form of the macro attribute, which is useful where an
+attribute can't be placed (such as when the `impl` block is generated by another macro). It takes an `impl` block or
+a module, and its arguments are given by an `#[enum_from_functions(...)]` attribute on that item.
+```
+# use enum_from_functions::enum_from_functions_inline;
+enum_from_functions_inline! {
+    #[enum_from_functions(display)]
+    impl Enum {
+        fn foo() -> &'static str {
+            "Foo"
+        }
+        fn bar(baz: i32) -> &'static str {
+            "Bar"
+        }
+    }
+}
+# fn main() {
+assert_eq!(Enum::Bar { baz: 1 }.map(), "Bar");
+assert_eq!(Enum::Foo.to_string(), "foo");
+# }
+```
 If you need to export the generated `enum` type out of its parent module, provide the `pub` argument to the macro
 attribute.
 ```
@@ -135,6 +610,36 @@ mod internal {
 // Causes a compile error because the generated `enum` is not visible outside of the `internal` module.
 use internal::NotVisible;
 ```
+Any other visibility (such as `pub(crate)`) can be provided in the same way. To give the `enum` and the `map` function
+different visibilities, use the `enum_vis` and `map_vis` arguments instead.
+```
+mod internal {
+#   use enum_from_functions::enum_from_functions;
+    #[enum_from_functions(enum_vis = pub, map_vis = pub(super))]
+    impl Visible {
+        fn example() -> bool {
+            true
+        }
+    }
+}
+
+// Will compile because `map` is visible to this module.
+let result = internal::Visible::Example.map();
+```
+```compile_fail
+mod internal {
+#   use enum_from_functions::enum_from_functions;
+    #[enum_from_functions(enum_vis = pub)]
+    impl Visible {
+        fn example() -> bool {
+            true
+        }
+    }
+}
+
+// Causes a compile error because `map` is private to the `internal` module.
+let result = internal::Visible::Example.map();
+```
 Items in the `impl` block that are not functions will be ignored and passed through to the output unchanged.
 Similarly, any attributes applied before *or* after the macro attribute will be applied to the generated `enum`
 declaration.
@@ -165,83 +670,1929 @@ impl Enum {
 #     let _ = format!("{:?}", Enum::Foo);
 # }
 ```
-*/
-
-mod extract;
-mod generate;
-
-use generate::WithoutTypes;
-use proc_macro::TokenStream;
-use proc_macro_error::{abort, emit_error, proc_macro_error};
-use quote::quote;
-use syn::{parse_macro_input, parse_quote, ExprBlock, Field, Fields, ItemImpl};
-
-/**
-A procedural macro attribute that generates an `enum` based on the functions defined in the `impl` block it annotates.
-See the crate documentation for more information.
-*/
-#[proc_macro_error]
-#[proc_macro_attribute]
-pub fn enum_from_functions(args: TokenStream, input: TokenStream) -> TokenStream {
-    let pub_token = match extract::pub_token(args) {
-        Ok(pub_token) => pub_token,
-        Err(err) => {
-            emit_error!(err.span(), err);
-            None
-        }
-    };
-
-    let (parsed_input, attributes) = {
-        let mut parsed_input = parse_macro_input!(input as ItemImpl);
-        let attributes = parsed_input.attrs.clone();
-        parsed_input.attrs.clear();
-        (parsed_input, attributes)
-    };
-
-    let enum_name = &*parsed_input.self_ty;
-    let functions = match extract::Functions::try_from(&parsed_input) {
-        Ok(functions) => functions,
-        Err(err) => abort!(err.span(), err),
-    };
-
-    // Unpack the struct here because we can't in the `quote` block.
-    let (return_type, asyncness, constness, unsafety, calls, variants) = {
-        (
-            &functions.return_type,
-            functions.asyncness,
-            functions.constness,
-            functions.unsafety,
-            &functions.calls,
-            generate::Variants::from(&functions),
-        )
-    };
-
-    let variants_iter = variants.0.iter();
-    let variant_names = variants.0.iter().map(|variant| &variant.ident);
-    let variant_fields = variants.0.iter().map(|variant| -> Option<ExprBlock> {
-        if let Fields::Named(fields) = &variant.fields {
-            let no_types = Field::without_types(&fields.named);
-            Some(parse_quote! { { #no_types } })
-        } else {
-            None
-        }
-    });
-
-    quote! {
-        #(#attributes)*
-        #pub_token enum #enum_name {
-            #(#variants_iter,)*
-        }
-
-        #parsed_input
+The `#[cfg(...)]` attributes of a function are applied to its variant, as well as to everything else that is
+generated for it (such as the arms of `map`), so functions can be conditionally compiled.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(count, names)]
+impl Enum {
+    fn foo() -> &'static str {
+        "Foo"
+    }
 
-        impl #enum_name {
-            #pub_token #asyncness #constness #unsafety fn map(self) #return_type {
-                match self {
-                    #(Self::#variant_names #variant_fields => #calls,)*
-                }
-            }
-        }
+    #[cfg(any())]
+    fn bar() -> &'static str {
+        "Bar"
     }
-    .into()
+}
+# fn main() {
+assert_eq!(Enum::COUNT, 1);
+assert_eq!(Enum::NAMES, ["foo"]);
+assert_eq!(Enum::Foo.map(), "Foo");
+# }
+```
+By default, the generated `map` function consumes the `enum`. Provide the `by_ref` argument to the macro attribute to
+have `map` borrow the `enum` instead. The fields of the variant will be cloned when they are passed to the function.
+Since that can't be done in a `const fn`, `map` is only `const` if none of the fields have to be cloned.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(by_ref)]
+impl Enum {
+    fn foo(baz: String) -> usize {
+        baz.len()
+    }
+    fn bar(&self) -> usize {
+        0
+    }
+}
+# fn main() {
+let commands = [Enum::Foo { baz: "Foo".to_owned() }, Enum::Bar {}];
+for _ in 0..2 {
+    assert_eq!(commands.iter().map(Enum::map).sum::<usize>(), 3);
+}
+# }
+```
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(by_ref)]
+impl Enum {
+    const fn foo(baz: u32) -> u32 {
+        baz
+    }
+}
+# fn main() {
+assert_eq!(Enum::Foo { baz: 1 }.map(), 1);
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+// Causes a compile error because `map` only has shared access to the `enum`.
+#[enum_from_functions(by_ref)]
+impl Enum {
+    fn foo(&mut self) {}
+}
+```
+The `by_mut` argument will instead have `map` take `&mut self`. Parameters of a `&mut` type are stored in the variant
+by value, and the function will be passed a mutable reference to the stored value. Any other fields are cloned as with
+`by_ref`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(by_mut)]
+impl Counter {
+    fn increment(count: &mut u32, step: u32) -> u32 {
+        *count += step;
+        *count
+    }
+}
+# fn main() {
+let mut counter = Counter::Increment { count: 0, step: 2 };
+counter.map();
+assert_eq!(counter.map(), 4);
+# }
+```
+Functions taking `&self` or `&mut self` are passed the `enum` itself, so their fields are cloned before the call, and
+they can't have parameters of a `&mut` type.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(by_mut)]
+##[derive(Debug)]
+impl Counter {
+    fn describe(&self, label: &str, count: u32) -> String {
+        format!("{label}: {count} ({self:?})")
+    }
+}
+# fn main() {
+let mut counter = Counter::Describe {
+    label: "total".to_owned(),
+    count: 1,
+};
+assert_eq!(
+    counter.map(),
+    r#"total: 1 (Describe { label: "total", count: 1 })"#
+);
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+// Causes a compile error because the count would be borrowed mutably while `self` is passed to the function.
+#[enum_from_functions(by_mut)]
+impl Counter {
+    fn increment(&self, count: &mut u32) {}
+}
+```
+Parameters of a shared reference type without a lifetime (such as `&str` or `&i32`) are stored in the variant as
+their [`ToOwned`](std::borrow::ToOwned) counterpart, and the function will be passed a reference to the stored value.
+The `map_ref` argument will generate a `map_ref` function that takes `&self` in addition to `map`, which leaves the
+`enum` intact.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(map_ref)]
+impl Greeting {
+    fn hello(name: &str) -> String {
+        format!("Hello, {name}!")
+    }
+}
+# fn main() {
+let greeting = Greeting::Hello {
+    name: "World".to_owned(),
+};
+assert_eq!(greeting.map_ref(), "Hello, World!");
+assert_eq!(greeting.map(), "Hello, World!");
+# }
+```
+The `lifetime` argument will instead store references without a lifetime as they are, by adding a lifetime parameter
+(`'a` unless another is provided, as in `lifetime = 'b`) to the `enum` and giving it to each of them.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(lifetime)]
+impl Greeting {
+    fn hello(name: &str) -> String {
+        format!("Hello, {name}!")
+    }
+}
+# fn main() {
+let name = String::from("World");
+let greeting: Greeting<'_> = Greeting::Hello { name: &name };
+assert_eq!(greeting.map(), "Hello, World!");
+# }
+```
+Variants are generated with named fields by default. Provide the `tuple_variants` argument to the macro attribute to
+generate tuple variants instead.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(tuple_variants)]
+impl Enum {
+    fn foo() -> i32 {
+        0
+    }
+    fn bar(baz: i32, qux: i32) -> i32 {
+        baz + qux
+    }
+}
+# fn main() {
+assert_eq!(Enum::Foo.map(), 0);
+assert_eq!(Enum::Bar(1, 2).map(), 3);
+# }
+```
+If every function in the `impl` block takes the same parameters, the `unit_variants` argument can be provided to the
+macro attribute to generate fieldless variants. The parameters are then taken by `map` instead, and passed on to
+whichever function is called.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(unit_variants)]
+impl Operation {
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+    fn subtract(a: i32, b: i32) -> i32 {
+        a - b
+    }
+}
+# fn main() {
+assert_eq!(Operation::Add.map(3, 2), 5);
+assert_eq!(Operation::Subtract.map(3, 2), 1);
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+// Causes a compile error because the parameters don't match.
+#[enum_from_functions(unit_variants)]
+impl Operation {
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+    fn negate(a: i32) -> i32 {
+        -a
+    }
+}
+```
+When every function needs access to some shared state, the `context` argument can be given its type. Every function
+must take a parameter of that type first (after any receiver), which isn't stored in the variants. `map` takes it
+instead (before any other parameters) and passes it on to whichever function is called.
+```
+# use enum_from_functions::enum_from_functions;
+struct Document {
+    text: String,
+}
+
+#[enum_from_functions(context = &mut Document)]
+impl Command {
+    fn insert(document: &mut Document, text: String) {
+        document.text.push_str(&text);
+    }
+    fn clear(document: &mut Document) {
+        document.text.clear();
+    }
+}
+# fn main() {
+let mut document = Document { text: String::new() };
+Command::Insert { text: String::from("hello") }.map(&mut document);
+assert_eq!(document.text, "hello");
+Command::Clear.map(&mut document);
+assert_eq!(document.text, "");
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+# struct Document;
+// Causes a compile error because `clear` doesn't take the context.
+#[enum_from_functions(context = &mut Document)]
+impl Command {
+    fn insert(document: &mut Document, text: String) {}
+    fn clear() {}
+}
+```
+The context can't be confused with a parameter that is named `context`.
+```
+# use enum_from_functions::enum_from_functions;
+struct Document {
+    zoom: i32,
+}
+
+#[enum_from_functions(context = &mut Document)]
+impl Command {
+    fn zoom(document: &mut Document, context: i32) {
+        document.zoom = context;
+    }
+}
+# fn main() {
+let mut document = Document { zoom: 1 };
+Command::Zoom { context: 2 }.map(&mut document);
+assert_eq!(document.zoom, 2);
+# }
+```
+The `arg_structs` argument will instead move the parameters of each function into a `struct` of their own, named
+after the variant followed by `Args`, which is wrapped by a tuple variant. Any `derive` attributes on the `enum` are
+also applied to these `struct`s.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(arg_structs)]
+##[derive(Clone, Debug)]
+impl Enum {
+    fn foo() -> i32 {
+        0
+    }
+    fn bar(baz: i32, qux: i32) -> i32 {
+        baz + qux
+    }
+}
+
+fn sum(args: &BarArgs) -> i32 {
+    args.baz + args.qux
+}
+# fn main() {
+let args = BarArgs { baz: 1, qux: 2 };
+assert_eq!(sum(&args), 3);
+assert_eq!(Enum::Foo.map(), 0);
+assert_eq!(Enum::Bar(args).map(), 3);
+# }
+```
+Attributes on the parameters of the functions are applied to the corresponding fields of the variants. Other than
+lint attributes, they are removed from the functions themselves. Parameters can't have `#[cfg(...)]` attributes, since
+the fields are used by everything that is generated for their variants.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl Command {
+    fn start_engine(
+        /// The speed to run the engine at.
+        speed: u32,
+    ) {
+    }
+}
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+impl Command {
+    fn start_engine(#[cfg(any())] speed: u32) {}
+}
+```
+This allows field-level `serde` attributes to be used. With the `serde` feature enabled, the `serde` argument will
+derive `Serialize` and `Deserialize` for the `enum` (and any argument `struct`s). The crate using the macro must
+depend on `serde` with its `derive` feature.
+```
+# use enum_from_functions::enum_from_functions;
+# #[cfg(feature = "serde")]
+#[enum_from_functions(serde)]
+##[serde(rename_all = "snake_case")]
+impl Command {
+    fn start_engine(#[serde(default)] speed: u32) {}
+}
+# #[cfg(feature = "serde")]
+# fn main() {
+#     assert!(matches!(
+#         serde_json::from_str(r#"{ "start_engine": {} }"#),
+#         Ok(Command::StartEngine { speed: 0 })
+#     ));
+# }
+# #[cfg(not(feature = "serde"))]
+# fn main() {}
+```
+With the `schemars` feature enabled, the `schemars` argument will derive `JsonSchema` for the `enum` (and any argument
+`struct`s) in the same way, which describes the fields of each variant and takes the `serde` attributes into account.
+The doc comments of the functions and their parameters become the descriptions of the variants and fields. The crate
+using the macro must depend on `schemars`.
+```
+# use enum_from_functions::enum_from_functions;
+# #[cfg(all(feature = "serde", feature = "schemars"))]
+#[enum_from_functions(serde, schemars)]
+impl Command {
+    /// Starts the engine.
+    fn start_engine(
+        /// The speed to run the engine at.
+        #[schemars(range(max = 100))]
+        speed: u32,
+    ) {
+    }
+}
+
+# #[cfg(all(feature = "serde", feature = "schemars"))]
+# fn main() {
+let schema = schemars::schema_for!(Command);
+# assert!(serde_json::to_string(&schema).unwrap().contains("The speed to run the engine at."));
+# }
+# #[cfg(not(all(feature = "serde", feature = "schemars")))]
+# fn main() {}
+```
+Similarly, with the `fuzz` feature enabled, the `arbitrary` argument will implement `arbitrary::Arbitrary` for the
+`enum`, which chooses a variant and then generates each of its fields. The crate using the macro must depend on
+`arbitrary`.
+```
+# use enum_from_functions::enum_from_functions;
+use arbitrary::{Arbitrary, Unstructured};
+
+# #[cfg(feature = "fuzz")]
+#[enum_from_functions(arbitrary)]
+impl Command {
+    fn start_engine(speed: u32) {}
+    fn stop_engine() {}
+}
+
+# #[cfg(feature = "fuzz")]
+# fn main() {
+// Such as in `fuzz_target!(|command: Command| command.map())`.
+let data = [1, 2, 3, 4, 5];
+Command::arbitrary(&mut Unstructured::new(&data)).unwrap().map();
+# }
+# #[cfg(not(feature = "fuzz"))]
+# fn main() {}
+```
+With the `clap` feature enabled, the `clap` argument will derive `clap::Parser` for the `enum`, so that each function
+is a subcommand whose parameters are its arguments, and parsing the command line gives a variant that `map` runs.
+Another trait can be derived instead with `clap = Subcommand` (to nest the `enum` in another command) or
+`clap = ValueEnum` (for fieldless variants). The doc comments of the functions become the help text of their
+subcommands, and the `enum` isn't documented with the functions since its documentation is the help text of the
+command. Since `#[arg(...)]` is forwarded to the fields for `clap`, the arguments of the macro are given to a
+parameter through `#[enum_from_functions(...)]` instead. The crate using the macro must depend on `clap` with its
+`derive` feature.
+```
+# use enum_from_functions::enum_from_functions;
+use clap::Parser;
+
+# #[cfg(feature = "clap")]
+#[enum_from_functions(clap)]
+/// Controls the engine.
+impl Command {
+    /// Starts the engine.
+    fn start_engine(#[arg(short, long)] speed: u32) {}
+    /// Stops the engine.
+    fn stop_engine(#[enum_from_functions(default = false)] force: bool) {}
+}
+
+# #[cfg(feature = "clap")]
+fn main() {
+    // Such as `engine start-engine --speed 3`.
+    Command::parse_from(["engine", "start-engine", "--speed", "3"]).map();
+}
+# #[cfg(not(feature = "clap"))]
+# fn main() {}
+```
+With the `wasm` feature enabled, the `wasm` argument will export the `enum` to JavaScript through `wasm_bindgen`: a
+fieldless `enum` named with a `Tag` suffix has a value for each variant, and a `dispatch_` function named after the
+`enum` calls `map` on the variant for a tag, given its parameters as a JSON array. The result is returned as JSON, and
+any error in the parameters is thrown in JavaScript. The functions can't be `async`, `unsafe` or have `#[cfg(...)]`
+attributes, the `impl` block can't be generic, and `map` can't take any parameters. The crate using the macro must
+depend on `wasm-bindgen` and `serde_json`, and the parameters and return types of the functions must implement
+`Deserialize` and `Serialize` respectively.
+```
+# use enum_from_functions::enum_from_functions;
+# #[cfg(feature = "wasm")]
+#[enum_from_functions(wasm)]
+impl Shape {
+    fn square(side: u32) -> u32 {
+        side * side
+    }
+    fn rectangle(width: u32, height: u32) -> u32 {
+        width * height
+    }
+}
+
+# #[cfg(feature = "wasm")]
+# fn main() {
+// Or in JavaScript, `dispatch_shape(ShapeTag.Rectangle, "[2, 3]")`.
+assert_eq!(dispatch_shape(ShapeTag::Rectangle, "[2, 3]").ok().as_deref(), Some("6"));
+# }
+# #[cfg(not(feature = "wasm"))]
+# fn main() {}
+```
+Similarly, with the `pyo3` feature enabled, the `pyo3` argument will generate a `#[pyfunction]` to call a function
+from Python by its name, given its parameters as keyword arguments. The `dispatch_` function (named after the `enum`)
+constructs the variant and returns the result of `map`, and has to be added to a `#[pymodule]`. The same restrictions
+as for `wasm` apply, other than `#[cfg(...)]` attributes being supported. The crate using the macro must depend on
+`pyo3`, and the parameters and return types of the functions must implement `FromPyObject` and `IntoPyObject`
+respectively.
+```
+# use enum_from_functions::enum_from_functions;
+use pyo3::prelude::*;
+
+# #[cfg(feature = "pyo3")]
+#[enum_from_functions(pyo3)]
+impl Shape {
+    fn square(side: u32) -> u32 {
+        side * side
+    }
+    fn rectangle(width: u32, height: u32) -> u32 {
+        width * height
+    }
+}
+
+# #[cfg(feature = "pyo3")]
+#[pymodule]
+fn shapes(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(dispatch_shape, module)?)
+}
+
+# #[cfg(feature = "pyo3")]
+# fn main() -> PyResult<()> {
+# pyo3::prepare_freethreaded_python();
+// Like `shapes.dispatch_shape("rectangle", width=2, height=3)` in Python.
+Python::with_gil(|py| {
+    let module = PyModule::new(py, "shapes")?;
+    shapes(&module)?;
+    let kwargs = pyo3::types::PyDict::new(py);
+    kwargs.set_item("width", 2)?;
+    kwargs.set_item("height", 3)?;
+    let area = module.getattr("dispatch_shape")?.call(("rectangle",), Some(&kwargs))?;
+    assert_eq!(area.extract::<u32>()?, 6);
+    Ok(())
+})
+# }
+# #[cfg(not(feature = "pyo3"))]
+# fn main() {}
+```
+With the `napi` feature enabled, the `napi` argument will export the `enum` to Node.js as a class through `napi`.
+The class wraps the `enum`, has a factory method for each function that constructs its variant from the parameters
+(other than those with a default value), and has a `map` method that calls `map` on a clone of the variant, which
+returns a promise if the functions are `async`. The `enum` must implement `Clone`, the functions can't be `unsafe`,
+the `impl` block can't be generic, and `map` can't take any parameters. The crate using the macro must depend on `napi`
+and `napi-derive` (with the `async` feature of `napi` for `async` functions).
+```
+# use enum_from_functions::enum_from_functions;
+# async fn download(url: &str) -> String {
+#     url.to_owned()
+# }
+# #[cfg(feature = "napi")]
+#[enum_from_functions(napi)]
+#[derive(Clone)]
+impl Shape {
+    fn square(side: u32) -> u32 {
+        side * side
+    }
+    async fn fetch(url: String) -> u32 {
+        download(&url).await.len() as u32
+    }
+}
+
+# #[cfg(feature = "napi")]
+# fn main() {
+// In JavaScript, `await Shape.square(3).map()` returns `9`, and `await Shape.fetch(url).map()` returns the length. The
+// class can also be used from Rust, where it is named `JsShape`.
+assert_eq!(futures::executor::block_on(JsShape::square(3).map()), 9);
+assert_eq!(futures::executor::block_on(JsShape::fetch("abc".to_owned()).map()), 3);
+# }
+# #[cfg(not(feature = "napi"))]
+# fn main() {}
+```
+If you would rather write the dispatching logic yourself, the `no_map` argument will prevent the `map` function from
+being generated.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(no_map)]
+impl Enum {
+    fn foo() {}
+}
+
+impl Enum {
+    fn map(&self) -> &'static str {
+        "Foo"
+    }
+}
+```
+To keep `map` but give it a different name (such as when one of the functions is named `map`), provide the name to
+the `map_name` argument. The functions that forward to `map` call it by that name instead.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(map_name = run)]
+impl Enum {
+    fn map(value: i32) -> i32 {
+        value * 2
+    }
+}
+# fn main() {
+assert_eq!(Enum::Map { value: 2 }.run(), 4);
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+// Causes a compile error because `map` would be defined twice.
+#[enum_from_functions]
+impl Enum {
+    fn map(value: i32) -> i32 {
+        value * 2
+    }
+}
+```
+The same goes for the functions generated by the other arguments, which can't share a name with any of the functions
+either.
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+// Causes a compile error because `len` would be defined twice.
+#[enum_from_functions(count)]
+impl Enum {
+    fn len(value: i32) -> i32 {
+        value * 2
+    }
+}
+```
+The `display` argument will generate an implementation of [`Display`](core::fmt::Display) for the `enum`, which
+prints the name of the function that corresponds to the variant.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(display)]
+impl Command {
+    fn start_engine(speed: u32) {}
+}
+# fn main() {
+assert_eq!(Command::StartEngine { speed: 0 }.to_string(), "start_engine");
+# }
+```
+The `debug` argument will generate an implementation of [`Debug`](core::fmt::Debug) for the `enum`, which formats
+the variant as a call to the corresponding function.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(debug)]
+impl Command {
+    fn start_engine(speed: u32, label: &'static str) {}
+    fn stop_engine() {}
+}
+# fn main() {
+let command = Command::StartEngine {
+    speed: 3,
+    label: "main",
+};
+assert_eq!(format!("{command:?}"), r#"start_engine(speed: 3, label: "main")"#);
+assert_eq!(format!("{:?}", Command::StopEngine), "stop_engine()");
+# }
+```
+The `name` argument will generate a `const fn name` that returns the name of the function that corresponds to the
+variant.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(name)]
+impl Command {
+    fn start_engine(speed: u32) {}
+}
+# fn main() {
+assert_eq!(Command::StartEngine { speed: 0 }.name(), "start_engine");
+# }
+```
+The `names` argument will generate a `NAMES` constant containing the name of every function, in the order that they
+were declared.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(names)]
+impl Command {
+    fn start_engine(speed: u32) {}
+    fn stop_engine() {}
+}
+# fn main() {
+assert_eq!(Command::NAMES, ["start_engine", "stop_engine"]);
+# }
+```
+The `rename_all` argument converts the names of the functions into another case wherever they are used as strings: by
+`display`, `from_str`, `names`, `name` and `strum_like`, by `kind`, and when calling a function by its name with
+`dispatch_by_name`, `registry`, `dispatch_json` or from Python with `pyo3`. The names of the variants are unaffected.
+The same cases are supported as by `serde`: `"lowercase"`, `"UPPERCASE"`, `"PascalCase"`, `"camelCase"`,
+`"snake_case"`, `"SCREAMING_SNAKE_CASE"`, `"kebab-case"` and `"SCREAMING-KEBAB-CASE"`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(rename_all = "kebab-case", display, from_str, names)]
+##[derive(Debug, PartialEq)]
+impl Command {
+    fn start_engine() {}
+    fn stop_engine() {}
+}
+# fn main() {
+assert_eq!(Command::StartEngine.to_string(), "start-engine");
+assert_eq!("stop-engine".parse(), Ok(Command::StopEngine));
+assert_eq!(Command::NAMES, ["start-engine", "stop-engine"]);
+# }
+```
+The `constructors` argument will generate a `const fn` for each variant that takes the same parameters as the
+corresponding function and returns the variant. Each constructor is named after its function, prefixed by `new_`.
+Parameters of the type of the `enum` are boxed by the constructor, which isn't a `const fn` in that case.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(constructors)]
+impl Command {
+    fn start_engine(speed: u32, gear: u8) {}
+    fn stop_engine() {}
+}
+# fn main() {
+assert!(matches!(
+    Command::new_start_engine(10, 1),
+    Command::StartEngine { speed: 10, gear: 1 }
+));
+assert!(matches!(Command::new_stop_engine(), Command::StopEngine));
+# }
+```
+A parameter can be given a default value with `#[arg(default = value)]`, in which case it is left out of the
+parameters of the constructor. Since the value might not be constant, a constructor that uses one isn't a `const fn`.
+The value is also used by the `Default` implementation (described below) in place of `Default::default()`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(constructors)]
+impl Command {
+    fn start_engine(speed: u32, #[arg(default = 1)] gear: u8) {}
+}
+# fn main() {
+assert!(matches!(
+    Command::new_start_engine(10),
+    Command::StartEngine { speed: 10, gear: 1 }
+));
+# }
+```
+The `kind` argument will generate a fieldless counterpart to the `enum` named after the `enum` followed by `Kind`,
+along with a `kind` function to convert to it. A different name can be provided with `kind = Name`. The fieldless
+`enum` derives the standard comparison traits, so that variants can be compared without comparing their fields.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(kind)]
+impl Command {
+    fn start(speed: u32) {}
+    fn stop() {}
+}
+# fn main() {
+let commands = [Command::Start { speed: 1 }, Command::Start { speed: 2 }, Command::Stop];
+assert_eq!(commands[0].kind(), commands[1].kind());
+assert_eq!(CommandKind::from(&commands[2]), CommandKind::Stop);
+# }
+```
+The fieldless `enum` also has a `from_name` function that looks up a variant by the name of its function, and a
+`const fn name` that does the opposite. The lookup is a `match` on the name, so no table has to be built at runtime.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(kind)]
+impl Command {
+    fn start(speed: u32) {}
+    fn stop() {}
+}
+# fn main() {
+assert_eq!(CommandKind::from_name("start"), Some(CommandKind::Start));
+assert_eq!(CommandKind::from_name("restart"), None);
+assert_eq!(CommandKind::Stop.name(), "stop");
+# }
+```
+The `accessors` argument will generate an `as_` and an `into_` function for each variant with fields, named after the
+corresponding function. They return the fields of the variant as a tuple (of references, in the case of `as_`) if the
+`enum` is that variant, and `None` otherwise.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(accessors)]
+impl Command {
+    fn start(speed: u32, name: String) {}
+    fn stop() {}
+}
+# fn main() {
+let command = Command::Start {
+    speed: 1,
+    name: "Engine".to_owned(),
+};
+assert_eq!(command.as_start(), Some((&1, &"Engine".to_owned())));
+assert_eq!(command.into_start(), Some((1, "Engine".to_owned())));
+assert_eq!(Command::Stop.into_start(), None);
+# }
+```
+The `visitor` argument will generate a trait named `<enum>Visitor` (or the name given) with a `visit_` method for
+each variant, named after the corresponding function and taking the fields of the variant, along with an `accept`
+function that calls the method for the variant. This allows the variants to be handled in another way than by calling
+the functions, without matching on them.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(visitor)]
+impl Command {
+    fn start(speed: u32) {}
+    fn stop() {}
+}
+
+struct Log(Vec<String>);
+
+impl CommandVisitor for Log {
+    fn visit_start(&mut self, speed: u32) {
+        self.0.push(format!("start at {speed}"));
+    }
+    fn visit_stop(&mut self) {
+        self.0.push("stop".to_owned());
+    }
+}
+# fn main() {
+let mut log = Log(Vec::new());
+Command::Start { speed: 1 }.accept(&mut log);
+Command::Stop.accept(&mut log);
+assert_eq!(log.0, ["start at 1", "stop"]);
+# }
+```
+If none of the functions take any parameters, the `from_str` argument will generate implementations of
+[`FromStr`](core::str::FromStr) and `TryFrom<&str>` for the `enum`, which parse the name of a function into the
+corresponding variant. An error type named `Parse` followed by the name of the `enum` and then `Error` is generated
+for when the string doesn't match any function.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(from_str)]
+##[derive(Debug, PartialEq)]
+impl Command {
+    fn start() {}
+    fn stop() {}
+}
+# fn main() {
+assert_eq!("start".parse(), Ok(Command::Start));
+assert_eq!(Command::try_from("stop"), Ok(Command::Stop));
+assert_eq!("restart".parse::<Command>(), Err(ParseCommandError));
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+// Causes a compile error because `start` takes a parameter.
+#[enum_from_functions(from_str)]
+impl Command {
+    fn start(speed: u32) {}
+}
+```
+If every function takes the same parameters (and none of them take `self`), the `dispatch_by_name` argument will
+generate a `dispatch_by_name` function that takes the name of a function along with its parameters, and calls that
+function. The same error type as `from_str` is returned if there is no function with that name.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(unit_variants, dispatch_by_name)]
+impl Operation {
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+    fn subtract(a: i32, b: i32) -> i32 {
+        a - b
+    }
+}
+# fn main() {
+assert_eq!(Operation::dispatch_by_name("add", 3, 2), Ok(5));
+assert_eq!(
+    Operation::dispatch_by_name("multiply", 3, 2),
+    Err(ParseOperationError)
+);
+# }
+```
+With the `inventory` feature enabled, the `registry` argument will generate a `dispatch_dynamic` function that works
+in the same way, except that names which don't belong to any of the functions are looked up in a registry built with
+the [`inventory`](https://docs.rs/inventory) crate. Other crates can add functions to the registry by submitting an
+`<enum>Registration` that holds a name and a pointer to the function. The functions can't be `async` or generic with
+this argument.
+```
+# use enum_from_functions::enum_from_functions;
+# #[cfg(feature = "inventory")]
+#[enum_from_functions(unit_variants, registry)]
+impl Command {
+    fn build(target: &str) -> String {
+        format!("built {target}")
+    }
+}
+
+// In another crate:
+fn deploy(target: &str) -> String {
+    format!("deployed {target}")
+}
+# #[cfg(feature = "inventory")]
+inventory::submit! {
+    CommandRegistration { name: "deploy", handler: deploy }
+}
+
+# #[cfg(feature = "inventory")]
+# fn main() {
+assert_eq!(Command::dispatch_dynamic("build", "app"), Ok("built app".to_owned()));
+assert_eq!(Command::dispatch_dynamic("deploy", "app"), Ok("deployed app".to_owned()));
+# }
+# #[cfg(not(feature = "inventory"))]
+# fn main() {}
+```
+With the `serde_json` feature enabled, the `dispatch_json` argument will generate a `dispatch_json` function that takes
+the name of a function and a JSON object of its parameters (as a `serde_json::Value`), deserializes the parameters
+into the fields of the corresponding variant and calls `map` on it. Parameters missing from the object are deserialized
+from `null`, so that those of an `Option` type can be left out. The `Dispatch<enum>Error` that it returns tells apart
+names that don't match any function and parameters that can't be deserialized. This requires that `map` is safe and
+doesn't take any parameters, and the `impl` block can't be generic.
+```
+# use enum_from_functions::enum_from_functions;
+# #[cfg(feature = "serde_json")]
+#[enum_from_functions(dispatch_json)]
+impl Rpc {
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+}
+
+# #[cfg(feature = "serde_json")]
+# fn main() {
+assert_eq!(Rpc::dispatch_json("add", serde_json::json!({ "a": 1, "b": 2 })).unwrap(), 3);
+assert!(matches!(
+    Rpc::dispatch_json("subtract", serde_json::Value::Null),
+    Err(DispatchRpcError::UnknownFunction)
+));
+# }
+# #[cfg(not(feature = "serde_json"))]
+# fn main() {}
+```
+Similarly, the `variants` argument will generate a `VARIANTS` constant containing every variant, and an `iter`
+function that returns an iterator over them.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(variants)]
+impl Check {
+    fn disk() -> bool {
+        true
+    }
+    fn network() -> bool {
+        true
+    }
+}
+# fn main() {
+assert_eq!(Check::VARIANTS.len(), 2);
+assert!(Check::iter().all(Check::map));
+# }
+```
+The `strum_like` argument provides the equivalent of `strum`'s `EnumIter`, `EnumString` and `IntoStaticStr` derives,
+without any attributes on the variants. It generates everything that `from_str` and `variants` do, along with
+conversions from the `enum` (or a reference to it) into the name of the corresponding function.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(strum_like)]
+##[derive(Debug, PartialEq)]
+impl Check {
+    fn disk() -> bool {
+        true
+    }
+    fn network() -> bool {
+        true
+    }
+}
+# fn main() {
+let names = Check::iter().map(<&'static str>::from).collect::<Vec<_>>();
+assert_eq!(names, ["disk", "network"]);
+assert_eq!("network".parse(), Ok(Check::Network));
+# }
+```
+The `count` argument will generate a `COUNT` constant and a `const fn len` containing the number of variants.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(count)]
+impl Command {
+    fn start(speed: u32) {}
+    fn stop() {}
+}
+# fn main() {
+let statistics = [0; Command::COUNT];
+assert_eq!(statistics.len(), Command::len());
+# }
+```
+With the `enum_map` feature enabled, the `enum_map` argument will implement `enum_map::Enum` and `enum_map::EnumArray`
+for an `enum` with fieldless variants, so that it can be used as the key of an `EnumMap`. The variants are numbered in
+the order that the functions were declared. The crate using the macro must depend on `enum_map`.
+```
+# use enum_from_functions::enum_from_functions;
+# #[cfg(feature = "enum_map")]
+#[enum_from_functions(enum_map)]
+impl Check {
+    fn disk() -> bool {
+        true
+    }
+    fn network() -> bool {
+        true
+    }
+}
+
+# #[cfg(feature = "enum_map")]
+# fn main() {
+let mut failures = enum_map::EnumMap::<Check, u32>::default();
+failures[Check::Network] += 1;
+# assert_eq!(failures.values().copied().collect::<Vec<_>>(), [0, 1]);
+# }
+# #[cfg(not(feature = "enum_map"))]
+# fn main() {}
+```
+The `ordering` argument will implement [`Ord`] (along with [`PartialOrd`], [`Eq`] and [`PartialEq`]) for the `enum`,
+ordering the variants by the order that the functions were declared in, regardless of their fields. Variants are
+therefore equal if they correspond to the same function, and the fields don't need to implement any of these traits.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(ordering)]
+##[derive(Debug)]
+impl Task {
+    fn urgent(description: String) {}
+    fn routine(description: String, hours: f32) {}
+}
+# fn main() {
+let mut queue = vec![
+    Task::Routine {
+        description: "Water the plants".to_owned(),
+        hours: 0.5,
+    },
+    Task::Urgent {
+        description: "Put out the fire".to_owned(),
+    },
+    Task::Routine {
+        description: "Feed the cat".to_owned(),
+        hours: 0.1,
+    },
+];
+queue.sort();
+assert!(matches!(queue[0], Task::Urgent { .. }));
+assert!(matches!(&queue[1], Task::Routine { description, .. } if description == "Water the plants"));
+assert_eq!(queue[1], queue[2]);
+# }
+```
+If the `enum` has a `#[repr(...)]` attribute with an integer type, the `repr_conversions` argument will generate
+conversions between the `enum` and that type, using the discriminants of the variants. Like `from_str`, this requires
+that none of the functions take any parameters, and an error type named `TryFrom` followed by the name of the `enum`
+and then `Error` is generated.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(repr_conversions)]
+##[repr(u8)]
+##[derive(Debug, PartialEq)]
+impl Tag {
+    #[enum_from_functions(discriminant = 4)]
+    fn four() {}
+    fn five() {}
+}
+# fn main() {
+assert_eq!(u8::from(Tag::Four), 4);
+assert_eq!(Tag::try_from(5), Ok(Tag::Five));
+assert_eq!(Tag::try_from(6), Err(TryFromTagError(6)));
+# }
+```
+If every function takes the same parameters (and none of them take `self` or are `async`), the `as_fn` argument will
+generate a `const fn as_fn` that returns a pointer to the function that corresponds to the variant. This is most
+useful in combination with `unit_variants`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(unit_variants, as_fn)]
+impl Operation {
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+    fn subtract(a: i32, b: i32) -> i32 {
+        a - b
+    }
+}
+
+struct Callback {
+    function: fn(i32, i32) -> i32,
+}
+# fn main() {
+let callback = Callback {
+    function: Operation::Subtract.as_fn(),
+};
+assert_eq!((callback.function)(3, 2), 1);
+# }
+```
+If the functions have an ABI, the pointer returned by `as_fn` has it too, so every function must have the same ABI.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(as_fn)]
+impl Callback {
+    extern "C" fn first() -> i32 {
+        1
+    }
+    extern "C" fn second() -> i32 {
+        2
+    }
+}
+# fn main() {
+let function: extern "C" fn() -> i32 = Callback::Second.as_fn();
+assert_eq!(function(), 2);
+# }
+```
+Under the same conditions, the `jump_table` argument will generate a `JUMP_TABLE` constant holding a pointer to every
+function in the order of the variants, and have `map` call the function for the variant through it rather than
+matching on the variant to call it directly. This avoids a branch for each function (such as when dispatching the
+opcodes of an interpreter), though the functions can't be `const` or have `#[cfg(...)]` attributes, and it can't be
+combined with the arguments that wrap the calls to the functions (`tracing`, `log`, `metrics` and `memoize`).
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(unit_variants, jump_table)]
+impl Opcode {
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+    fn subtract(a: i32, b: i32) -> i32 {
+        a - b
+    }
+}
+# fn main() {
+assert_eq!(Opcode::Subtract.map(3, 2), 1);
+assert_eq!((Opcode::JUMP_TABLE[0])(3, 2), 5);
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+// Causes a compile error because the results of the functions can't be memoized when they are called through the
+// table.
+#[enum_from_functions(jump_table, memoize)]
+impl Opcode {
+    fn nop() -> i32 {
+        0
+    }
+}
+```
+If none of the functions take any parameters (including `self`), the `map_all` argument will generate a `map_all`
+function that calls every function in the order that they were declared and returns their results in an array.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(map_all)]
+impl Number {
+    const fn one() -> u8 {
+        1
+    }
+    const fn two() -> u8 {
+        2
+    }
+    const fn three() -> u8 {
+        3
+    }
+}
+# fn main() {
+const NUMBERS: [u8; 3] = Number::map_all();
+assert_eq!(NUMBERS, [1, 2, 3]);
+# }
+```
+The `map_batch` argument will generate a `map_batch` function that takes any number of variants, calls `map` on each of
+them in order and returns their results in a `Vec`. If `map` is `async`, so is `map_batch`, which awaits each call
+before making the next one. This requires that `map` doesn't take any parameters.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(map_batch)]
+impl Command {
+    fn start(speed: u32) -> String {
+        format!("start at {speed}")
+    }
+    fn stop() -> String {
+        "stop".to_owned()
+    }
+}
+# fn main() {
+let queue = vec![Command::Start { speed: 1 }, Command::Stop];
+assert_eq!(Command::map_batch(queue), ["start at 1", "stop"]);
+# }
+```
+With the `rayon` feature enabled, the `map_batch_par` argument will generate a `map_batch_par` function that calls
+`map` on each variant in parallel using [`rayon`](https://docs.rs/rayon), and returns their results in order. It takes
+a `Vec` of variants, or a slice (or mutable slice) of them if `map` takes `&self` (or `&mut self`), which requires that
+the `enum` is `Send` (or `Sync` when `map` takes `&self`) and that the functions return a type that is `Send`. `map`
+can't be `async` or take any parameters.
+```
+# use enum_from_functions::enum_from_functions;
+# const TEXT: &str = "one two\nthree";
+# fn count_lines() -> usize {
+#     TEXT.lines().count()
+# }
+# fn count_words() -> usize {
+#     TEXT.split_whitespace().count()
+# }
+# #[cfg(feature = "rayon")]
+#[enum_from_functions(by_ref, map_batch_par)]
+impl Analysis {
+    fn lines() -> usize {
+        count_lines()
+    }
+    fn words() -> usize {
+        count_words()
+    }
+}
+
+# #[cfg(feature = "rayon")]
+# fn main() {
+let results = Analysis::map_batch_par(&[Analysis::Lines, Analysis::Words]);
+# assert_eq!(results, [2, 3]);
+# }
+# #[cfg(not(feature = "rayon"))]
+# fn main() {}
+```
+With the `tokio` feature enabled, the `map_batch_concurrent` argument will generate an `async` function called
+`map_batch_concurrent` that calls `map` on each variant in a task of its own, spawned in a `tokio::task::JoinSet`. At
+most `limit` tasks run at once, and the results are returned in the order of the variants. This requires that the
+functions are `async`, that `map` takes `self` and no other parameters, and that the `enum` and the type returned by
+the functions are `Send + 'static`. If a task panics, the panic is resumed once the task is joined.
+```
+# use enum_from_functions::enum_from_functions;
+# async fn download(url: &str) -> String {
+#     url.to_uppercase()
+# }
+# #[cfg(feature = "tokio")]
+#[enum_from_functions(map_batch_concurrent)]
+impl Job {
+    async fn fetch(url: String) -> String {
+        download(&url).await
+    }
+}
+
+# #[cfg(feature = "tokio")]
+# #[tokio::main]
+# async fn main() {
+# let urls = ["a", "b", "c"].map(String::from);
+let jobs = urls.into_iter().map(|url| Job::Fetch { url });
+let pages = Job::map_batch_concurrent(jobs, 2).await;
+# assert_eq!(pages, ["A", "B", "C"]);
+# }
+# #[cfg(not(feature = "tokio"))]
+# fn main() {}
+```
+The `memoize` argument will have `map` call each function without any parameters (including `self`) only the first time
+that its variant is mapped, storing the result in a `static` and returning a clone of it from then on. The result is
+shared with the other functions that call it, such as `map_ref`. The functions can't be `async` or `const`, and the
+`impl` block can't be generic.
+```
+# use enum_from_functions::enum_from_functions;
+# use std::sync::atomic::{AtomicU32, Ordering};
+static CALLS: AtomicU32 = AtomicU32::new(0);
+#[enum_from_functions(memoize, map_ref)]
+impl Setting {
+    fn path() -> String {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        String::from("/etc/app.toml")
+    }
+    fn double(value: u32) -> String {
+        (value * 2).to_string()
+    }
+}
+# fn main() {
+assert_eq!(Setting::Path.map(), "/etc/app.toml");
+assert_eq!(Setting::Path.map_ref(), "/etc/app.toml");
+assert_eq!(Setting::Path.map(), "/etc/app.toml");
+assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+assert_eq!(Setting::Double { value: 2 }.map(), "4");
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(map_all)]
+impl Number {
+    fn one() -> u8 {
+        1
+    }
+    fn other(n: u8) -> u8 {
+        n
+    }
+}
+# fn main() {}
+```
+The `dispatch` argument will implement the [`Dispatch`] trait for the `enum`, which allows generic code to call `map`
+on any `enum` generated by this macro. This requires that `map` is safe and doesn't take any parameters. If any of the
+functions are `async`, `boxed_future` must also be provided.
+```
+# use enum_from_functions::enum_from_functions;
+use enum_from_functions::Dispatch;
+
+#[enum_from_functions(dispatch)]
+impl Greeting {
+    fn hello() -> String {
+        "Hello".to_owned()
+    }
+}
+
+#[enum_from_functions(dispatch, by_ref)]
+impl Farewell {
+    fn goodbye(name: String) -> String {
+        format!("Goodbye, {}", name)
+    }
+}
+
+fn run<T: Dispatch<Output = String>>(command: T) -> String {
+    command.dispatch()
+}
+# fn main() {
+assert_eq!(run(Greeting::Hello), "Hello");
+assert_eq!(
+    run(Farewell::Goodbye {
+        name: "World".to_owned()
+    }),
+    "Goodbye, World"
+);
+# }
+```
+Similarly, the `dyn_dispatch` argument will implement the [`DynDispatch`] trait, which takes a boxed `enum` and
+returns a boxed future, so that `enum`s with `async` functions can be called through a trait object. This requires that
+`map` is safe and doesn't take any parameters, and the `impl` block can't be generic.
+```
+# use enum_from_functions::enum_from_functions;
+use enum_from_functions::DynDispatch;
+
+#[enum_from_functions(dyn_dispatch)]
+impl Download {
+    async fn fetch(url: String) -> String {
+        format!("fetched {url}")
+    }
+}
+
+#[enum_from_functions(dyn_dispatch)]
+impl Upload {
+    async fn send() -> String {
+        "sent".to_owned()
+    }
+}
+# fn main() {
+let jobs: Vec<Box<dyn DynDispatch<Output = String>>> = vec![
+    Box::new(Download::Fetch { url: "a".to_owned() }),
+    Box::new(Upload::Send),
+];
+let mut outputs = Vec::new();
+for job in jobs {
+    outputs.push(futures::executor::block_on(job.dispatch()));
+}
+assert_eq!(outputs, ["fetched a", "sent"]);
+# }
+```
+The `into_fn` argument will generate an `into_fn` function that converts the variant into a closure which forwards to
+`map`, so that it can be passed to code expecting a closure. The closure implements [`FnOnce`], [`FnMut`] or [`Fn`],
+for when `map` takes `self`, `&mut self` or `&self` respectively. Like `dispatch`, this requires that `map` is safe,
+and that `boxed_future` is provided if any of the functions are `async`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(into_fn)]
+impl Command {
+    fn shout(message: String) -> String {
+        message.to_uppercase()
+    }
+}
+
+fn run<T>(f: impl FnOnce() -> T) -> T {
+    f()
+}
+# fn main() {
+let command = Command::Shout {
+    message: "hello".to_owned(),
+};
+assert_eq!(run(command.into_fn()), "HELLO");
+# }
+```
+The `private_functions` argument removes the visibility from every function in the `impl` block, so that the only way
+to call them from outside of their module is through the generated `enum`.
+```
+mod internal {
+#   use enum_from_functions::enum_from_functions;
+    #[enum_from_functions(pub, private_functions)]
+    impl Visible {
+        pub fn example() -> bool {
+            true
+        }
+    }
+}
+
+// Will compile because `map` is still public.
+assert!(internal::Visible::Example.map());
+```
+```compile_fail
+mod internal {
+#   use enum_from_functions::enum_from_functions;
+    #[enum_from_functions(pub, private_functions)]
+    impl Visible {
+        pub fn example() -> bool {
+            true
+        }
+    }
+}
+
+// Causes a compile error because `example` is no longer public.
+assert!(internal::Visible::example());
+```
+The `free_fn` argument will additionally generate a free function named `map_` followed by the name of the `enum` in
+`snake_case`, which forwards to `map`. A different name can be provided with `free_fn = name`. The free function has
+the same visibility as the `enum`, so combining this argument with `map_vis` allows the free function to be the only
+public way of calling `map`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(free_fn)]
+impl MyEnum {
+    fn foo() -> i32 {
+        1
+    }
+    fn bar(baz: i32) -> i32 {
+        baz
+    }
+}
+# fn main() {
+let dispatch: fn(MyEnum) -> i32 = map_my_enum;
+assert_eq!(dispatch(MyEnum::Foo), 1);
+assert_eq!(dispatch(MyEnum::Bar { baz: 2 }), 2);
+# }
+```
+With `unit_variants`, the free function also takes the parameters of the functions, whatever they are named.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(free_fn, unit_variants)]
+impl MyEnum {
+    fn double(e: i32) -> i32 {
+        e * 2
+    }
+}
+# fn main() {
+assert_eq!(map_my_enum(MyEnum::Double, 2), 4);
+# }
+```
+The `match_macro` argument will generate a declarative macro named `match_` followed by the name of the `enum` in
+`snake_case`, which matches on a value of the `enum` and requires an arm for every variant (in the order that the
+functions were declared). This keeps code that handles each variant separately exhaustive as functions are added. A
+different name can be provided with `match_macro = name`. Like any declarative macro, it can only be used after
+the `impl` block. It can't be used with functions that have `#[cfg(...)]` attributes.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(match_macro)]
+impl Command {
+    fn start_engine(speed: u32) {}
+    fn stop_engine() {}
+}
+# fn main() {
+let describe = |command| {
+    match_command!(command, {
+        StartEngine { speed } => format!("starting at {speed}"),
+        StopEngine => "stopping".to_owned(),
+    })
+};
+assert_eq!(describe(Command::StartEngine { speed: 3 }), "starting at 3");
+assert_eq!(describe(Command::StopEngine), "stopping");
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(match_macro)]
+impl Command {
+    fn start_engine(speed: u32) {}
+    fn stop_engine() {}
+}
+# fn main() {
+// Causes a compile error because `StopEngine` isn't handled.
+match_command!(Command::StopEngine, {
+    StartEngine { .. } => (),
+});
+# }
+```
+The `ffi` argument will similarly generate an `extern "C"` function named `extern_map_` followed by the name of the
+`enum` in `snake_case`, which can be handed to C code as a function pointer. A different name can be provided with
+`ffi = name`. The `enum` is given a `#[repr(C)]` attribute, unless it already has a `#[repr(...)]` attribute.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(ffi)]
+impl Signal {
+    fn start() -> i32 {
+        1
+    }
+    fn stop() -> i32 {
+        0
+    }
+}
+# fn main() {
+let callback: extern "C" fn(Signal) -> i32 = extern_map_signal;
+assert_eq!(callback(Signal::Start), 1);
+# }
+```
+Functions that return `impl Trait` return a different type each, so `map` can't return `impl Trait` itself. The
+`box_return` argument will have `map` return `Box<dyn Trait>` instead, boxing the value returned by each function. A
+different type can be provided to box the values in, as in `box_return = std::rc::Rc`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(box_return)]
+impl Enum {
+    fn up() -> impl Iterator<Item = u8> {
+        0..3
+    }
+    fn down() -> impl Iterator<Item = u8> {
+        (0..3).rev()
+    }
+}
+# fn main() {
+let iter: Box<dyn Iterator<Item = u8>> = Enum::Down.map();
+assert_eq!(iter.collect::<Vec<_>>(), [2, 1, 0]);
+# }
+```
+The `output_enum` argument allows the functions to have different return types altogether. `map` will return another
+`enum` named after the `enum` followed by `Output` (or the name provided, as in `output_enum = Name`), which has a
+variant for each function holding the value that it returned. The traits that it derives can be given after the
+argument, as in `output_enum(derive(Debug, PartialEq))` or `output_enum = Name(derive(Debug))`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(output_enum(derive(Debug, PartialEq)))]
+impl Enum {
+    fn number() -> u16 {
+        1337
+    }
+    fn text() -> String {
+        String::from("Foo")
+    }
+    fn nothing() {}
+}
+# fn main() {
+assert_eq!(Enum::Number.map(), EnumOutput::Number(1337));
+assert_eq!(Enum::Text.map(), EnumOutput::Text(String::from("Foo")));
+assert_eq!(Enum::Nothing.map(), EnumOutput::Nothing);
+# }
+```
+If the return types of the functions all implement the same trait, the `return_as` argument can be used to have
+`map` return a trait object instead, such as `return_as = Box<dyn Trait>`. The value returned by each function is
+passed to the `new` function of the type given (`Box::new` in this case).
+```
+# use enum_from_functions::enum_from_functions;
+use std::fmt::Display;
+
+#[enum_from_functions(return_as = Box<dyn Display>)]
+impl Enum {
+    fn number() -> u16 {
+        1337
+    }
+    fn text() -> &'static str {
+        "Foo"
+    }
+}
+# fn main() {
+assert_eq!(Enum::Number.map().to_string(), "1337");
+assert_eq!(Enum::Text.map().to_string(), "Foo");
+# }
+```
+If every function returns a `Result` with the same success type, the `try_map` argument will generate a `try_map`
+function that converts the errors returned by the functions into the type provided (using [`Into`]). The error types
+of the functions can then be different, in which case `map` isn't generated.
+```
+# use enum_from_functions::enum_from_functions;
+#[derive(Debug, PartialEq)]
+struct Error(String);
+
+impl From<std::num::ParseIntError> for Error {
+    fn from(err: std::num::ParseIntError) -> Self {
+        Self(err.to_string())
+    }
+}
+
+impl From<&'static str> for Error {
+    fn from(err: &'static str) -> Self {
+        Self(err.to_owned())
+    }
+}
+
+#[enum_from_functions(try_map = Error)]
+impl Enum {
+    fn parse(text: String) -> Result<u8, std::num::ParseIntError> {
+        text.parse()
+    }
+    fn fail() -> Result<u8, &'static str> {
+        Err("failed")
+    }
+}
+# fn main() {
+assert_eq!(Enum::Parse { text: "1".to_owned() }.try_map(), Ok(1));
+assert_eq!(Enum::Fail.try_map(), Err(Error("failed".to_owned())));
+# }
+```
+The `map_catch` argument will generate a `map_catch` function that calls each function inside of
+[`std::panic::catch_unwind`], returning the payload of the panic as an error if the function panics. Functions can't be
+`async` with this argument.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(map_catch)]
+impl Plugin {
+    fn run(input: u8) -> u8 {
+        input * 2
+    }
+    fn crash() -> u8 {
+        panic!("crashed")
+    }
+}
+# fn main() {
+assert_eq!(Plugin::Run { input: 2 }.map_catch().unwrap(), 4);
+let payload = Plugin::Crash.map_catch().unwrap_err();
+assert_eq!(payload.downcast_ref::<&str>(), Some(&"crashed"));
+# }
+```
+The `timed` argument will generate a `map_timed` function that returns how long the call took alongside the result of
+the function.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(timed)]
+impl Enum {
+    fn foo() -> &'static str {
+        "Foo"
+    }
+}
+# fn main() {
+let (output, duration) = Enum::Foo.map_timed();
+assert_eq!(output, "Foo");
+assert!(duration < std::time::Duration::from_secs(1));
+# }
+```
+With the `metrics` feature enabled, the `metrics` argument has `map` record the duration of every call in a histogram
+of the [`metrics`](https://docs.rs/metrics) crate, labelled with the name of the variant as `variant`. The histogram is
+named `<enum>_duration_seconds` (in snake case) by default, and another name can be given as a string.
+```
+# use enum_from_functions::enum_from_functions;
+# #[cfg(feature = "metrics")]
+#[enum_from_functions(metrics = "command_duration_seconds")]
+impl Command {
+    fn build() {
+        // ...
+    }
+    fn test() {
+        // ...
+    }
+}
+
+# #[cfg(feature = "metrics")]
+# fn main() {
+// Records the duration in `command_duration_seconds{variant="Build"}`.
+Command::Build.map();
+# }
+# #[cfg(not(feature = "metrics"))]
+# fn main() {}
+```
+The `boxed_future` argument will have `map` return a boxed future (`Pin<Box<dyn Future<Output = T> + Send>>`) rather than
+being an `async fn`. This makes the future returned by `map` nameable, so that it can be stored or returned from a
+trait object.
+```
+# use enum_from_functions::enum_from_functions;
+use std::{future::Future, pin::Pin};
+
+#[enum_from_functions(boxed_future)]
+impl Enum {
+    async fn foo() -> &'static str {
+        "Foo"
+    }
+    fn bar() -> &'static str {
+        "Bar"
+    }
+}
+
+struct Pending {
+    future: Pin<Box<dyn Future<Output = &'static str> + Send>>,
+}
+# fn main() {
+let pending = Pending {
+    future: Enum::Foo.map(),
+};
+assert_eq!(futures::executor::block_on(pending.future), "Foo");
+assert_eq!(futures::executor::block_on(Enum::Bar.map()), "Bar");
+# }
+```
+The `force_async` argument will make `map` an `async fn` even if none of the functions are `async`, so that an `async`
+function can be added later without changing the signature of `map` for its callers.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(force_async)]
+impl Enum {
+    fn foo() -> &'static str {
+        "Foo"
+    }
+}
+# fn main() {
+assert_eq!(futures::executor::block_on(Enum::Foo.map()), "Foo");
+# }
+```
+The `maybe_async` argument follows the pattern of the `maybe-async` crate, so that the same functions can be compiled
+as either `async` or blocking. With the `is_sync` feature enabled, `async` is removed from the functions and `.await`
+from their bodies, which makes `map` blocking. Otherwise, the argument has no effect. Any `async` functions that they
+call need to be converted in the same way (such as with `#[maybe_async::maybe_async]`).
+```
+# use enum_from_functions::enum_from_functions;
+# struct Response;
+# struct Client;
+# impl Client {
+#     fn get(&self, _: &str) -> &Self {
+#         self
+#     }
+#     #[cfg(feature = "is_sync")]
+#     fn send(&self) -> Response {
+#         Response
+#     }
+#     #[cfg(not(feature = "is_sync"))]
+#     async fn send(&self) -> Response {
+#         Response
+#     }
+# }
+# fn client() -> Client {
+#     Client
+# }
+#[enum_from_functions(maybe_async)]
+impl Request {
+    async fn get(url: String) -> Response {
+        client().get(&url).send().await
+    }
+}
+
+# fn main() {
+# let url = String::new();
+# #[cfg(feature = "is_sync")]
+# {
+// With `is_sync`:
+let response = Request::Get { url }.map();
+# }
+# #[cfg(not(feature = "is_sync"))]
+# futures::executor::block_on(async {
+// Without `is_sync`:
+let response = Request::Get { url }.map().await;
+# });
+# }
+```
+Only the `.await` of calls (of the functions converted by `maybe_async`) is removed. Nested `async` blocks and closures
+are left intact, along with anything that they await.
+```
+# use enum_from_functions::enum_from_functions;
+#[cfg(feature = "is_sync")]
+fn double(value: u32) -> u32 {
+    value * 2
+}
+#[cfg(not(feature = "is_sync"))]
+async fn double(value: u32) -> u32 {
+    value * 2
+}
+async fn increment(value: u32) -> u32 {
+    value + 1
+}
+
+#[enum_from_functions(maybe_async)]
+impl Request {
+    async fn get(id: u32) -> u32 {
+        let next = futures::FutureExt::now_or_never(async move { increment(id).await });
+        double(next.unwrap()).await
+    }
+}
+# fn main() {
+#[cfg(feature = "is_sync")]
+assert_eq!(Request::Get { id: 1 }.map(), 4);
+#[cfg(not(feature = "is_sync"))]
+assert_eq!(futures::executor::block_on(Request::Get { id: 1 }.map()), 4);
+# }
+```
+With the `tracing` feature enabled, the `tracing` argument makes `map` call each function inside of a `tracing` span
+named after its variant. The span is entered for the duration of the call, or attached to the future of an `async`
+function. Writing `tracing = fields` also records the fields of the variant in the span using their `Debug`
+implementations.
+```
+# use enum_from_functions::enum_from_functions;
+# #[cfg(feature = "tracing")]
+#[enum_from_functions(tracing = fields)]
+impl Enum {
+    async fn foo(bar: u32) -> u32 {
+        tracing::info!("called");
+        bar
+    }
+    async fn baz(name: String) -> u32 {
+        name.len() as u32
+    }
+}
+
+# #[cfg(feature = "tracing")]
+# fn main() {
+# futures::executor::block_on(async {
+// Logs `Foo{bar=1}: called`.
+Enum::Foo { bar: 1 }.map().await;
+# assert_eq!(Enum::Baz { name: "qux".to_owned() }.map().await, 3);
+# });
+# }
+# #[cfg(not(feature = "tracing"))]
+# fn main() {}
+```
+Similarly, with the `log` feature enabled, the `log` argument makes `map` log a debug message naming the function with
+`log::debug!` before calling it. The crate using the macro must depend on `log`.
+```
+# use enum_from_functions::enum_from_functions;
+# #[cfg(feature = "log")]
+#[enum_from_functions(log)]
+impl Enum {
+    fn foo(bar: u32) -> u32 {
+        bar
+    }
+}
+
+# #[cfg(feature = "log")]
+# fn main() {
+// Logs `dispatching foo`.
+Enum::Foo { bar: 1 }.map();
+# }
+# #[cfg(not(feature = "log"))]
+# fn main() {}
+```
+The `inline` argument will apply the `#[inline]` attribute to the generated `map` function. Like the attribute itself,
+it can also be written as `inline(always)` or `inline(never)`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(inline(always))]
+impl Enum {
+    fn foo() -> &'static str {
+        "Foo"
+    }
+}
+# fn main() {
+assert_eq!(Enum::Foo.map(), "Foo");
+# }
+```
+The `non_exhaustive` argument will mark the generated `enum` as `#[non_exhaustive]`. An expression can also be
+provided to this argument, in which case it is used for a wildcard arm in the `match` inside of `map`. This ensures
+`map` continues to compile if variants are added to the `enum` by other means, e.g. another macro.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(non_exhaustive = "Unknown")]
+impl Enum {
+    fn foo() -> &'static str {
+        "Foo"
+    }
+}
+# fn main() {
+assert_eq!(Enum::Foo.map(), "Foo");
+# }
+```
+The `prefix` and `suffix` arguments can be used to add text to the beginning or end of the name of every variant.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(prefix = "Cmd", suffix = "Now")]
+impl Command {
+    fn start() -> &'static str {
+        "Start"
+    }
+}
+# fn main() {
+assert_eq!(Command::CmdStartNow.map(), "Start");
+# }
+```
+The `module` argument places the generated `enum` and `map` function inside of a new module with the given name. The
+generated `enum` is still re-exported from the parent module (with the same visibility as the `enum`), so that the
+`impl` block can refer to it and it can be reached through either path.
+```
+mod cli {
+    # use enum_from_functions::enum_from_functions;
+    #[enum_from_functions(pub, module = commands)]
+    impl Command {
+        fn start() -> &'static str {
+            "Start"
+        }
+    }
+}
+# fn main() {
+assert_eq!(cli::commands::Command::Start.map(), "Start");
+assert_eq!(cli::Command::Start.map(), "Start");
+# }
+```
+The macro attribute can also be applied to individual functions within the `impl` block to configure the variant that
+is generated for that function. The `discriminant` argument sets an explicit discriminant for the variant.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+##[repr(u8)]
+impl Tag {
+    #[enum_from_functions(discriminant = 4)]
+    fn four() {}
+    fn five() {}
+}
+# fn main() {
+assert_eq!(Tag::Four as u8, 4);
+assert_eq!(Tag::Five as u8, 5);
+# }
+```
+The `default` argument implements [`Default`] for the `enum`, using the variant for that function. Any fields of the
+variant are set to their default values. Only one function can be marked as `default`.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions]
+##[derive(Debug, PartialEq)]
+impl Action {
+    fn sleep(seconds: u64) {}
+    #[enum_from_functions(default)]
+    fn nothing() {}
+}
+# fn main() {
+assert_eq!(Action::default(), Action::Nothing);
+# }
+```
+```compile_fail
+# use enum_from_functions::enum_from_functions;
+// Causes a compile error because more than one function is marked as `default`.
+#[enum_from_functions]
+impl Action {
+    #[enum_from_functions(default)]
+    fn sleep(seconds: u64) {}
+    #[enum_from_functions(default)]
+    fn nothing() {}
+}
+```
+The `boxed` argument stores each parameter of the function in a `Box`, so that a large parameter doesn't increase the
+size of every variant. `map` moves the parameters out of their boxes (or borrows them) before calling the function, and
+`constructors` take the parameters without boxes.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(constructors)]
+impl Command {
+    #[enum_from_functions(boxed)]
+    fn configure(config: [u8; 4096]) -> usize {
+        config.len()
+    }
+    fn stop() -> usize {
+        0
+    }
+}
+# fn main() {
+assert!(std::mem::size_of::<Command>() < 4096);
+assert_eq!(Command::new_configure([0; 4096]).map(), 4096);
+# }
+```
+
+The `generate_tests` argument will generate a `#[cfg(test)]` module named after the `enum` (such as `action_tests` for
+`Action`), with a test for each function that constructs its variant from the default values of its fields and checks
+that `map` calls that function. To check this, each function records its name in a marker when it is called in a test.
+The functions can't be `async`, `const` or `unsafe`, the `impl` block can't be generic, and the functions added by
+`extend` aren't tested.
+```
+# use enum_from_functions::enum_from_functions;
+#[enum_from_functions(generate_tests)]
+impl Action {
+    fn sleep(seconds: u64) -> String {
+        format!("slept for {seconds} seconds")
+    }
+    fn greet(name: &str, #[arg(default = 3)] times: u8) -> String {
+        name.repeat(times.into())
+    }
+}
+# fn main() {
+assert_eq!(Action::Sleep { seconds: 1 }.map(), "slept for 1 seconds");
+# }
+```
+
+The generated code only refers to items in `::core` and `alloc`, so the macro can be used in `#![no_std]` crates (the
+items from `alloc` are re-exported by this crate, so the crate using the macro doesn't have to declare `alloc` itself).
+The values that have to be allocated are stored in a [`Box`](alloc::boxed::Box): the futures returned by
+`boxed_future` and `dyn_dispatch`, the values returned by `box_return` (unless another type is given), the closures
+held by `custom`, the parameters of functions marked `boxed`, and parameters of the type of the `enum` itself. The
+JSON returned by `wasm` and the keys of the parameters of `dispatch_json` are held in a
+[`String`](alloc::string::String), the results of `map_batch` (and the functions like it) in a
+[`Vec`](alloc::vec::Vec), and shared references without a lifetime are stored by their owned counterpart through
+[`ToOwned`](alloc::borrow::ToOwned), which can be avoided with the `lifetime` argument. The exceptions that require
+`std` are the results stored by `memoize`, which are held in an `::std::sync::OnceLock`, the marker used by
+`generate_tests` in a `::std::thread_local!`, `map_catch`, which catches panics with `::std::panic::catch_unwind`, and
+`timed` and `metrics`, which measure durations with `::std::time::Instant`.
+*/
+
+#![no_std]
+
+// Only used to link to items that the generated code refers to.
+#[cfg(doc)]
+extern crate std;
+
+extern crate alloc;
+
+pub use enum_from_functions_macros::{enum_from_functions, enum_from_functions_inline};
+
+/// The items that the generated code refers to: those from `alloc`, which are re-exported so that crates using the macro
+/// don't have to declare `alloc` themselves, and the macro that collects the functions of the `impl` blocks marked with
+/// `extend`.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::{borrow::ToOwned, boxed::Box, format, string::String, vec::Vec};
+    pub use enum_from_functions_macros::extend;
+}
+
+/**
+A trait implemented by `enum`s generated with the `dispatch` argument, which allows generic code to call `map` on any of
+them.
+*/
+pub trait Dispatch {
+    /// The type returned by the functions.
+    type Output;
+
+    /// Calls the function that corresponds to the variant (i.e. `map`).
+    fn dispatch(self) -> Self::Output;
+}
+
+/**
+An object-safe counterpart to [`Dispatch`], implemented by `enum`s generated with the `dyn_dispatch` argument. The
+future returned by `map` is boxed, so that `enum`s with `async` functions can be called through a trait object.
+*/
+pub trait DynDispatch {
+    /// The type returned by the functions.
+    type Output;
+
+    /// Calls the function that corresponds to the variant (i.e. `map`), returning a boxed future.
+    fn dispatch(
+        self: alloc::boxed::Box<Self>,
+    ) -> core::pin::Pin<alloc::boxed::Box<dyn core::future::Future<Output = Self::Output> + Send>>;
 }